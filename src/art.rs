@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+
+use crate::{MprisResult, PlayerMetadata};
+
+/// Represents errors that can occur while resolving a track artwork.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ArtError {
+    #[error("Unsupported artwork url scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Invalid artwork data uri: {0}")]
+    InvalidDataUri(String),
+
+    #[error("Failed to download artwork: {0}")]
+    FailedToDownload(String),
+
+    #[error("Failed to read artwork: {0}")]
+    FailedToRead(String),
+
+    #[error("Failed to save artwork: {0}")]
+    FailedToSave(String),
+}
+
+/// Where the artwork of a track lives, resolved from its `mpris:artUrl`.
+///
+/// Players point to their artwork in different ways: browsers and streaming
+/// services usually hand out `http(s)://` urls, local players tend to use
+/// `file://` urls, and a few inline the image as a `data:` uri.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::{ArtSource, Mpris};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mpris = Mpris::new().await?;
+///
+///     for player in mpris.list_players().await? {
+///         if let Some(art) = player.metadata().await?.art()? {
+///             art.save(format!("{}.jpg", player.identity().short())).await?;
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtSource {
+    /// An artwork that has to be downloaded.
+    Http(String),
+
+    /// An artwork stored on the local filesystem.
+    File(PathBuf),
+
+    /// An artwork inlined in a `data:` uri.
+    Data {
+        /// The mime type of the artwork, if the uri has one.
+        mime: Option<String>,
+
+        /// The decoded artwork.
+        bytes: Vec<u8>,
+    },
+}
+
+impl ArtSource {
+    /// Resolves an artwork url.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ArtError::UnsupportedScheme`] if the url is not an http(s), file or data url,
+    /// or an [`ArtError::InvalidDataUri`] if the data uri can't be decoded.
+    pub fn parse(url: &str) -> MprisResult<Self> {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            return Ok(ArtSource::Http(url.to_string()));
+        }
+
+        if let Some(path) = url.strip_prefix("file://") {
+            // Drops the host part of the url, e.g. `file://localhost/...`.
+            let path = &path[path.find('/').unwrap_or(path.len())..];
+            let path = String::from_utf8(percent_decode(path))
+                .map_err(|err| ArtError::FailedToRead(err.to_string()))?;
+
+            return Ok(ArtSource::File(PathBuf::from(path)));
+        }
+
+        if let Some(data) = url.strip_prefix("data:") {
+            return parse_data_uri(data);
+        }
+
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or(url);
+        Err(ArtError::UnsupportedScheme(scheme.to_string()).into())
+    }
+
+    /// Gets the artwork bytes, downloading or reading it if needed.
+    pub async fn fetch(&self) -> MprisResult<Vec<u8>> {
+        match self {
+            ArtSource::Http(url) => {
+                let response = reqwest::get(url)
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|err| ArtError::FailedToDownload(err.to_string()))?;
+
+                let bytes = response
+                    .bytes()
+                    .await
+                    .map_err(|err| ArtError::FailedToDownload(err.to_string()))?;
+
+                Ok(bytes.to_vec())
+            }
+            ArtSource::File(path) => Ok(tokio::fs::read(path)
+                .await
+                .map_err(|err| ArtError::FailedToRead(format!("{}: {err}", path.display())))?),
+            ArtSource::Data { bytes, .. } => Ok(bytes.clone()),
+        }
+    }
+
+    /// Saves the artwork to the given path.
+    ///
+    /// Local artworks are copied as is, without being read into memory first.
+    pub async fn save(&self, path: impl AsRef<Path>) -> MprisResult<()> {
+        let path = path.as_ref();
+
+        if let ArtSource::File(source) = self {
+            tokio::fs::copy(source, path)
+                .await
+                .map_err(|err| ArtError::FailedToSave(format!("{}: {err}", path.display())))?;
+
+            return Ok(());
+        }
+
+        let bytes = self.fetch().await?;
+        tokio::fs::write(path, bytes)
+            .await
+            .map_err(|err| ArtError::FailedToSave(format!("{}: {err}", path.display())))?;
+
+        Ok(())
+    }
+}
+
+impl PlayerMetadata<'_> {
+    /// Resolves the artwork of the track from mpris:artUrl.
+    ///
+    /// Returns None when mpris:artUrl doesn't exists.
+    pub fn art(&self) -> MprisResult<Option<ArtSource>> {
+        self.art_url()?
+            .map(|url| ArtSource::parse(&url))
+            .transpose()
+    }
+}
+
+/// Parses the part of a data uri after `data:`, e.g. `image/png;base64,iVBOR...`.
+fn parse_data_uri(data: &str) -> MprisResult<ArtSource> {
+    let (header, payload) = data
+        .split_once(',')
+        .ok_or_else(|| ArtError::InvalidDataUri("missing `,`".into()))?;
+
+    let (mime, is_base64) = match header.strip_suffix(";base64") {
+        Some(mime) => (mime, true),
+        None => (header, false),
+    };
+
+    let bytes = if is_base64 {
+        base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|err| ArtError::InvalidDataUri(err.to_string()))?
+    } else {
+        percent_decode(payload)
+    };
+
+    // Drops parameters like `;charset=...` from the mime type.
+    let mime = mime.split(';').next().unwrap_or_default();
+    let mime = (!mime.is_empty()).then(|| mime.to_string());
+
+    Ok(ArtSource::Data { mime, bytes })
+}
+
+/// Decodes `%XX` escapes, leaving malformed ones as they are.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    decoded
+}
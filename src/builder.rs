@@ -0,0 +1,406 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    convert::Infallible,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use zbus::{Connection, connection};
+
+use crate::{
+    EventCapacity, EventFilter, EventMask, Mpris, MprisError, MprisOptions, MprisResult,
+    NowPlaying, PlaybackStatus, PlayerIdentity,
+};
+
+/// Sets up an [`Mpris`] before it connects, as returned by [`Mpris::builder`].
+///
+/// Unlike the [`MprisOptions`], which can be changed while watching, the mask and the
+/// player filter are fixed once the connection is built.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use mprizzle::{EventCapacity, EventMask, Mpris, OverflowPolicy};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::builder()
+///         .with_poll_interval(Duration::from_millis(500))
+///         .with_event_capacity(Some(EventCapacity::new(64, OverflowPolicy::DropOldest)))
+///         .with_mask(EventMask::ATTACH | EventMask::DETACH | EventMask::METADATA)
+///         .with_players(|identity| identity.short() != "firefox")
+///         .build()
+///         .await?;
+///
+///     mpris.watch();
+///
+///     while let Ok(event) = mpris.recv().await? {
+///         println!("{}: {:?}", event.identity().short(), event.kind());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct MprisBuilder {
+    bus: BusKind,
+    extra_buses: Vec<BusKind>,
+    connection: Option<Connection>,
+    options: MprisOptions,
+    mask: EventMask,
+    players: PlayerFilter,
+    ranking: PlayerRanking,
+}
+
+impl MprisBuilder {
+    /// Starts off with the defaults of [`Mpris::new`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the bus the players are looked for on, the session bus by default.
+    pub fn with_bus(mut self, bus: BusKind) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// Watches another bus alongside the main one, merging the events of their players.
+    ///
+    /// The identities of the players on the extra buses carry the bus they're on,
+    /// see [`PlayerIdentity::source`]. Their connections always get opened by mprizzle.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::{BusKind, Mpris};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::builder()
+    ///         .with_extra_bus(BusKind::Address("unix:path=/run/user/1001/bus".into()))
+    ///         .build()
+    ///         .await?;
+    ///
+    ///     mpris.watch();
+    ///
+    ///     while let Ok(event) = mpris.recv().await? {
+    ///         println!("{:?} {}: {:?}", event.identity().source(), event.identity().short(), event.kind());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_extra_bus(mut self, bus: BusKind) -> Self {
+        self.extra_buses.push(bus);
+        self
+    }
+
+    /// Shares a connection the application made already, instead of opening one.
+    ///
+    /// Takes over the bus set with [`MprisBuilder::with_bus`]. Unlike the connections opened
+    /// by mprizzle, it doesn't get reopened when it breaks, see [`Mpris::watch`].
+    pub fn with_connection(mut self, connection: Connection) -> Self {
+        self.connection = Some(connection);
+        self
+    }
+
+    /// Sets the options, replacing the ones set so far.
+    pub fn with_options(mut self, options: MprisOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets how often the players get polled, see [`MprisOptions::with_poll_interval`].
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.options = self.options.with_poll_interval(poll_interval);
+        self
+    }
+
+    /// Bounds the event channel, see [`MprisOptions::with_event_capacity`].
+    pub fn with_event_capacity(mut self, event_capacity: Option<EventCapacity>) -> Self {
+        self.options = self.options.with_event_capacity(event_capacity);
+        self
+    }
+
+    /// Stops the watch once the last player detached, see [`MprisOptions::with_exit_when_idle`].
+    pub fn with_exit_when_idle(mut self, exit_when_idle: bool) -> Self {
+        self.options = self.options.with_exit_when_idle(exit_when_idle);
+        self
+    }
+
+    /// Sets how many events a subscription can fall behind, see
+    /// [`MprisOptions::with_subscription_capacity`].
+    pub fn with_subscription_capacity(mut self, subscription_capacity: usize) -> Self {
+        self.options = self
+            .options
+            .with_subscription_capacity(subscription_capacity);
+        self
+    }
+
+    /// Sets which events get sent out, see [`MprisOptions::with_filter`].
+    pub fn with_filter(mut self, filter: EventFilter) -> Self {
+        self.options = self.options.with_filter(filter);
+        self
+    }
+
+    /// Sets the events that can be sent out at all, see [`EventMask`].
+    pub fn with_mask(mut self, mask: EventMask) -> Self {
+        self.mask = mask;
+        self
+    }
+
+    /// Sets which players get watched and listed, every one by default.
+    ///
+    /// The players left out are skipped altogether, no watch task is spawned for them.
+    /// Replaces the player filters set so far, unlike [`MprisBuilder::ignore_players`]
+    /// and [`MprisBuilder::only_players`].
+    pub fn with_players(
+        mut self,
+        players: impl Fn(&PlayerIdentity) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.players = PlayerFilter(Some(Arc::new(players)));
+        self
+    }
+
+    /// Leaves out the players matching any of the names, on top of the other player filters.
+    ///
+    /// The names are glob patterns matched with [`PlayerIdentity::matches_glob`],
+    /// a short name leaves out every instance of the player already.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mpris = Mpris::builder()
+    ///         .ignore_players(["firefox", "chromium", "kdeconnect"])
+    ///         .build()
+    ///         .await?;
+    ///
+    ///     mpris.watch();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn ignore_players<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+
+        self.players = self
+            .players
+            .and(move |identity| !names.iter().any(|name| identity.matches_glob(name)));
+        self
+    }
+
+    /// Only keeps the players matching one of the names, on top of the other player filters.
+    ///
+    /// The names are glob patterns matched with [`PlayerIdentity::matches_glob`].
+    pub fn only_players<S: Into<String>>(mut self, names: impl IntoIterator<Item = S>) -> Self {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+
+        self.players = self
+            .players
+            .and(move |identity| names.iter().any(|name| identity.matches_glob(name)));
+        self
+    }
+
+    /// Sets how the active player of [`Mpris::now_playing`] gets picked, the greater one winning.
+    ///
+    /// The players ranked equal go by the default, the playing player that started playing
+    /// last, or else the player that played last.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     // Anything over the browser, even when paused.
+    ///     let mpris = Mpris::builder()
+    ///         .with_ranking(|a, b| {
+    ///             let rank = |now_playing: &mprizzle::NowPlaying| now_playing.identity().short() != "firefox";
+    ///             rank(a).cmp(&rank(b))
+    ///         })
+    ///         .build()
+    ///         .await?;
+    ///
+    ///     mpris.watch();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn with_ranking(
+        mut self,
+        ranking: impl Fn(&NowPlaying, &NowPlaying) -> Ordering + Send + Sync + 'static,
+    ) -> Self {
+        self.ranking = PlayerRanking(Some(Arc::new(ranking)));
+        self
+    }
+
+    /// Prefers the players earlier in the list among the playing ones, then among the others,
+    /// see [`MprisBuilder::with_ranking`].
+    ///
+    /// The names are glob patterns matched with [`PlayerIdentity::matches_glob`], the players
+    /// left out of the list come last.
+    pub fn with_priority<S: Into<String>>(self, priority: impl IntoIterator<Item = S>) -> Self {
+        let priority: Vec<String> = priority.into_iter().map(Into::into).collect();
+
+        self.with_ranking(move |a, b| {
+            let rank = |now_playing: &NowPlaying| {
+                let position = priority
+                    .iter()
+                    .position(|name| now_playing.identity().matches_glob(name))
+                    .unwrap_or(priority.len());
+
+                (
+                    now_playing.status() == Some(&PlaybackStatus::Playing),
+                    Reverse(position),
+                )
+            };
+
+            rank(a).cmp(&rank(b))
+        })
+    }
+
+    /// Connects to D-Bus, unless given a connection, then to the extra buses.
+    pub async fn build(self) -> MprisResult<Mpris> {
+        let (connection, bus) = match self.connection {
+            Some(connection) => (connection, None),
+            None => (self.bus.connect().await?, Some(self.bus)),
+        };
+
+        let mut extra_connections = Vec::with_capacity(self.extra_buses.len());
+        for bus in self.extra_buses {
+            extra_connections.push((bus.connect().await?, bus));
+        }
+
+        Ok(Mpris::build(
+            connection,
+            bus,
+            extra_connections,
+            self.options,
+            self.mask,
+            self.players,
+            self.ranking,
+        ))
+    }
+
+    /// Builds the connection like [`MprisBuilder::build`], then replays the events recorded
+    /// by an [`crate::EventRecorder`] through it, see [`Mpris::replay`].
+    ///
+    /// The replayed players are only backed by the connection, a private bus set with
+    /// [`MprisBuilder::with_bus`] or [`MprisBuilder::with_connection`] does as well as the session one.
+    #[cfg(feature = "recording")]
+    pub async fn replay(self, path: impl AsRef<std::path::Path>) -> MprisResult<Mpris> {
+        let records = crate::recording::read_records(path)?;
+        let mpris = self.build().await?;
+
+        mpris.start_replay(records);
+
+        Ok(mpris)
+    }
+}
+
+/// The D-Bus bus an [`Mpris`] connects to, see [`MprisBuilder::with_bus`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum BusKind {
+    /// The bus of the user session, where the desktop players are.
+    #[default]
+    Session,
+
+    /// The bus of the whole system, where the headless players of some setups are.
+    System,
+
+    /// A bus at an explicit address (e.g., `unix:path=/run/user/1000/bus`),
+    /// like the one of a container.
+    Address(String),
+}
+
+impl BusKind {
+    /// Connects to the bus.
+    pub(crate) async fn connect(&self) -> MprisResult<Connection> {
+        let connection = match self {
+            BusKind::Session => Connection::session().await,
+            BusKind::System => Connection::system().await,
+            BusKind::Address(address) => match connection::Builder::address(address.as_str()) {
+                Ok(builder) => builder.build().await,
+                Err(err) => Err(err),
+            },
+        };
+
+        connection.map_err(|err| MprisError::FailedToConnectDbus(err.to_string()))
+    }
+}
+
+impl FromStr for BusKind {
+    type Err = Infallible;
+
+    /// Parses `session`, `system`, or else takes it as an address.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "session" => BusKind::Session,
+            "system" => BusKind::System,
+            address => BusKind::Address(address.to_string()),
+        })
+    }
+}
+
+/// The closure of [`MprisBuilder::with_players`].
+type PlayerPredicate = Arc<dyn Fn(&PlayerIdentity) -> bool + Send + Sync>;
+
+/// Decides which players an [`Mpris`] cares about.
+#[derive(Clone, Default)]
+pub(crate) struct PlayerFilter(Option<PlayerPredicate>);
+
+impl PlayerFilter {
+    /// Returns `true` if the player gets watched and listed.
+    pub(crate) fn matches(&self, identity: &PlayerIdentity) -> bool {
+        self.0.as_ref().is_none_or(|predicate| predicate(identity))
+    }
+
+    /// Keeps the players matching both this filter and the predicate.
+    fn and(self, predicate: impl Fn(&PlayerIdentity) -> bool + Send + Sync + 'static) -> Self {
+        Self(Some(match self.0 {
+            Some(previous) => Arc::new(move |identity| previous(identity) && predicate(identity)),
+            None => Arc::new(predicate),
+        }))
+    }
+}
+
+impl fmt::Debug for PlayerFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PlayerFilter")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+/// The closure of [`MprisBuilder::with_ranking`].
+type PlayerComparator = Arc<dyn Fn(&NowPlaying, &NowPlaying) -> Ordering + Send + Sync>;
+
+/// Decides which player is the active one, before the default does.
+#[derive(Clone, Default)]
+pub(crate) struct PlayerRanking(Option<PlayerComparator>);
+
+impl PlayerRanking {
+    /// Compares the players, Equal leaving it to the default.
+    pub(crate) fn compare(&self, a: &NowPlaying, b: &NowPlaying) -> Ordering {
+        self.0
+            .as_ref()
+            .map_or(Ordering::Equal, |comparator| comparator(a, b))
+    }
+}
+
+impl fmt::Debug for PlayerRanking {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PlayerRanking")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
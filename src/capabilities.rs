@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use zvariant::OwnedValue;
+
+/// The player properties telling what can be done with it.
+pub(crate) const CAPABILITY_PROPERTIES: [&str; 6] = [
+    "CanPlay",
+    "CanPause",
+    "CanSeek",
+    "CanGoNext",
+    "CanGoPrevious",
+    "CanControl",
+];
+
+/// What can be done with a player, as returned by [`crate::MprisPlayer::capabilities`].
+///
+/// The capabilities a player leaves out are taken as false.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    can_play: bool,
+    can_pause: bool,
+    can_seek: bool,
+    can_go_next: bool,
+    can_go_previous: bool,
+    can_control: bool,
+}
+
+impl Capabilities {
+    /// Picks the capabilities out of the player properties.
+    pub(crate) fn new(properties: &HashMap<String, OwnedValue>) -> Self {
+        let mut capabilities = Self::default();
+        capabilities.update(properties);
+        capabilities
+    }
+
+    /// Takes in the capabilities found in the changed properties, keeping the others.
+    pub(crate) fn update(&mut self, properties: &HashMap<String, OwnedValue>) {
+        let capability = |property: &str, current: &mut bool| {
+            if let Some(value) = properties
+                .get(property)
+                .and_then(|value| bool::try_from(value).ok())
+            {
+                *current = value;
+            }
+        };
+
+        capability("CanPlay", &mut self.can_play);
+        capability("CanPause", &mut self.can_pause);
+        capability("CanSeek", &mut self.can_seek);
+        capability("CanGoNext", &mut self.can_go_next);
+        capability("CanGoPrevious", &mut self.can_go_previous);
+        capability("CanControl", &mut self.can_control);
+    }
+
+    /// Can the playback be started.
+    pub fn can_play(&self) -> bool {
+        self.can_play
+    }
+
+    /// Can the playback be paused.
+    pub fn can_pause(&self) -> bool {
+        self.can_pause
+    }
+
+    /// Can the position be changed.
+    pub fn can_seek(&self) -> bool {
+        self.can_seek
+    }
+
+    /// Can the player skip to the next track.
+    pub fn can_go_next(&self) -> bool {
+        self.can_go_next
+    }
+
+    /// Can the player skip to the previous track.
+    pub fn can_go_previous(&self) -> bool {
+        self.can_go_previous
+    }
+
+    /// Can the player be controlled at all.
+    pub fn can_control(&self) -> bool {
+        self.can_control
+    }
+}
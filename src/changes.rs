@@ -0,0 +1,265 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use serde::{
+    Deserialize, Deserializer,
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+};
+use zvariant::{Signature, Type, Value};
+
+use crate::{PlaybackStatus, PlayerMetadata};
+
+/// The body of a `PropertiesChanged` signal, borrowed from the message.
+///
+/// The values of the changed properties get hashed rather than deserialized,
+/// as the watch tasks mostly look at which properties changed, and whether they
+/// really did. This matters for the players sending their whole `Metadata` on every
+/// change, which is only kept for telling the tracks apart. The new `PlaybackStatus`
+/// is kept as well, for the status events not to read it back from the player.
+#[derive(Debug, Deserialize, Type)]
+pub(crate) struct PropertiesChanges<'a> {
+    /// The interface the properties belong to.
+    #[serde(borrow)]
+    interface: &'a str,
+
+    /// The changed properties, with a hash of their values.
+    #[serde(borrow)]
+    changed: ChangedProperties<'a>,
+
+    /// The properties that changed, but whose values weren't sent.
+    #[serde(borrow)]
+    invalidated: Vec<&'a str>,
+}
+
+impl<'a> PropertiesChanges<'a> {
+    /// Gets the interface the properties belong to.
+    pub(crate) fn interface(&self) -> &'a str {
+        self.interface
+    }
+
+    /// Returns `true` if the property is one of the changed ones.
+    pub(crate) fn contains(&self, property: &str) -> bool {
+        self.changed
+            .properties
+            .iter()
+            .any(|(name, _)| *name == property)
+            || self.invalidated.contains(&property)
+    }
+
+    /// Iterates over the names of both the changed and invalidated properties.
+    pub(crate) fn properties(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.changed
+            .properties
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(self.invalidated.iter().copied())
+    }
+
+    /// Iterates over the changed properties, along with a hash of their new values.
+    pub(crate) fn fingerprints(&self) -> impl Iterator<Item = (&'a str, u64)> + '_ {
+        self.changed.properties.iter().copied()
+    }
+
+    /// Gets the properties that changed, but whose values weren't sent.
+    pub(crate) fn invalidated(&self) -> &[&'a str] {
+        &self.invalidated
+    }
+
+    /// Takes the new `Metadata`, if it was sent along.
+    pub(crate) fn take_metadata(&mut self) -> Option<PlayerMetadata<'a>> {
+        let metadata = self.changed.metadata.take()?;
+        HashMap::try_from(metadata).ok().map(PlayerMetadata::new)
+    }
+
+    /// Gets the new `PlaybackStatus`, if it was sent along.
+    pub(crate) fn playback_status(&self) -> Option<PlaybackStatus> {
+        let playback_status = self.changed.playback_status.as_ref()?;
+        PlaybackStatus::from_str(<&str>::try_from(playback_status).ok()?).ok()
+    }
+}
+
+/// The changed properties of a `PropertiesChanged` signal, as a `a{sv}` dict.
+///
+/// Only the names and a hash of the values are kept, except for the `Metadata` and `PlaybackStatus` values.
+#[derive(Debug, Default)]
+struct ChangedProperties<'a> {
+    properties: Vec<(&'a str, u64)>,
+    metadata: Option<Value<'a>>,
+    playback_status: Option<Value<'a>>,
+}
+
+impl Type for ChangedProperties<'_> {
+    const SIGNATURE: &'static Signature =
+        &Signature::static_dict(&Signature::Str, &Signature::Variant);
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for ChangedProperties<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ChangedPropertiesVisitor(PhantomData))
+    }
+}
+
+struct ChangedPropertiesVisitor<'a>(PhantomData<&'a ()>);
+
+impl<'de: 'a, 'a> Visitor<'de> for ChangedPropertiesVisitor<'a> {
+    type Value = ChangedProperties<'a>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a dict of changed properties")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut changed = ChangedProperties::default();
+
+        while let Some(name) = map.next_key::<&'a str>()? {
+            let fingerprint = match name {
+                "Metadata" | "PlaybackStatus" => {
+                    let value = map.next_value::<Value>()?;
+                    let mut hasher = DefaultHasher::new();
+
+                    value.hash(&mut hasher);
+                    let fingerprint = hasher.finish();
+
+                    match name {
+                        "Metadata" => changed.metadata = Some(value),
+                        _ => changed.playback_status = Some(value),
+                    }
+
+                    fingerprint
+                }
+                _ => map.next_value::<Fingerprinted>()?.0,
+            };
+
+            changed.properties.push((name, fingerprint));
+        }
+
+        Ok(changed)
+    }
+}
+
+/// A variant that got hashed rather than deserialized, to tell if it changed.
+#[derive(Debug)]
+pub(crate) struct Fingerprinted(u64);
+
+impl Type for Fingerprinted {
+    const SIGNATURE: &'static Signature = &Signature::Variant;
+}
+
+impl<'de> Deserialize<'de> for Fingerprinted {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut hasher = DefaultHasher::new();
+
+        Fingerprint(&Signature::Variant, &mut hasher).deserialize(deserializer)?;
+        Ok(Fingerprinted(hasher.finish()))
+    }
+}
+
+/// Hashes a value of the given signature as it gets read.
+///
+/// The signature has to be known, as the signature of a variant is read the same way as a sequence element.
+struct Fingerprint<'s, 'h>(&'s Signature, &'h mut DefaultHasher);
+
+impl<'de> DeserializeSeed<'de> for Fingerprint<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Fingerprint(signature, hasher) = self;
+
+        match signature {
+            Signature::Variant | Signature::Array(_) | Signature::Structure(_) => {
+                deserializer.deserialize_seq(Fingerprint(signature, hasher))
+            }
+            Signature::Dict { .. } => deserializer.deserialize_map(Fingerprint(signature, hasher)),
+            Signature::U8 => u8::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::Bool => bool::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::I16 => i16::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::U16 => u16::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::I32 => i32::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::U32 => u32::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::I64 => i64::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::U64 => u64::deserialize(deserializer).map(|value| value.hash(hasher)),
+            Signature::F64 => {
+                f64::deserialize(deserializer).map(|value| value.to_bits().hash(hasher))
+            }
+            Signature::Str | Signature::ObjectPath => {
+                <&str>::deserialize(deserializer).map(|value| value.hash(hasher))
+            }
+            Signature::Signature => {
+                Signature::deserialize(deserializer).map(|value| value.hash(hasher))
+            }
+            _ => deserializer.deserialize_ignored_any(IgnoredAny).map(|_| ()),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for Fingerprint<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a value of signature {}", self.0)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Fingerprint(signature, hasher) = self;
+
+        match signature {
+            Signature::Variant => {
+                let signature = seq
+                    .next_element::<Signature>()?
+                    .ok_or_else(|| de::Error::custom("missing the variant signature"))?;
+
+                signature.hash(hasher);
+                seq.next_element_seed(Fingerprint(&signature, hasher))?;
+            }
+            Signature::Array(child) => {
+                while seq
+                    .next_element_seed(Fingerprint(child, &mut *hasher))?
+                    .is_some()
+                {}
+            }
+            Signature::Structure(fields) => {
+                for field in fields.iter() {
+                    seq.next_element_seed(Fingerprint(field, &mut *hasher))?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let Fingerprint(signature, hasher) = self;
+
+        if let Signature::Dict { key, value } = signature {
+            while map.next_key_seed(Fingerprint(key, &mut *hasher))?.is_some() {
+                map.next_value_seed(Fingerprint(value, &mut *hasher))?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand};
+use mprizzle::{BusKind, Mpris, PlayerMetadata};
+
+mod art;
+mod daemon;
+mod doctor;
+mod monitor;
+mod position;
+mod select;
+mod watch;
+
+use daemon::QueryKind;
+use position::PositionArg;
+use select::PlayerSelector;
+use watch::WatchOnly;
+
+/// A shorthand for the result type of the cli commands.
+pub type CliResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+/// Control and inspect mpris players from the command line.
+#[derive(Debug, Parser)]
+#[command(name = "mprizzle", version, about)]
+pub struct Cli {
+    /// The player(s) to act on: a short name, a glob (e.g. `chromium*`), `active` or `all`.
+    #[arg(short, long, global = true, value_name = "PLAYER")]
+    player: Option<PlayerSelector>,
+
+    /// Comma separated player names (or globs) to prefer when picking the active player.
+    #[arg(
+        long,
+        global = true,
+        env = "MPRIZZLE_PRIORITY",
+        value_delimiter = ',',
+        value_name = "PLAYERS"
+    )]
+    priority: Vec<String>,
+
+    /// The bus to look for the players on: `session`, `system` or an address
+    /// (e.g. `unix:path=/run/user/1000/bus`).
+    #[arg(
+        long,
+        global = true,
+        env = "MPRIZZLE_BUS",
+        default_value = "session",
+        value_name = "BUS"
+    )]
+    bus: BusKind,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List the players.
+    List,
+
+    /// Print the playback status.
+    Status,
+
+    /// Print the metadata of the current track.
+    Metadata,
+
+    /// Start playback.
+    Play,
+
+    /// Pause playback.
+    Pause,
+
+    /// Toggle between playing and paused.
+    PlayPause,
+
+    /// Stop playback.
+    Stop,
+
+    /// Skip to the next track.
+    Next,
+
+    /// Skip to the previous track.
+    Previous,
+
+    /// Print the position in seconds, or seek to `30`, `5+`, `5-` or `50%`.
+    Position {
+        /// An absolute position, a relative offset, or a percentage of the track length.
+        value: Option<PositionArg>,
+    },
+
+    /// Print the artwork url of the current track, or save the artwork to a file.
+    Art {
+        /// Save the artwork to this path, downloading it if needed.
+        #[arg(long, value_name = "PATH")]
+        save: Option<PathBuf>,
+
+        /// Print the artwork url (the default when `--save` is not passed).
+        #[arg(long)]
+        url: bool,
+    },
+
+    /// Check the player against the MPRIS spec, for telling apart player bugs from mprizzle ones.
+    Doctor {
+        /// The player to check, instead of `--player`.
+        player: Option<PlayerSelector>,
+    },
+
+    /// Print mpris events as they happen.
+    Watch {
+        /// Comma separated kinds of events to print, e.g. `status,metadata`.
+        #[arg(long, value_delimiter = ',', value_name = "KINDS")]
+        only: Vec<WatchOnly>,
+    },
+
+    /// Print the mpris traffic on the bus (calls, replies and signals), for debugging controllers.
+    Monitor,
+
+    /// Run a daemon keeping the state of the players, for fast `mprizzle query` calls.
+    Daemon {
+        /// The unix socket to listen on.
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+    },
+
+    /// Ask the daemon, instead of connecting to D-Bus.
+    Query {
+        /// What to ask for.
+        kind: QueryKind,
+
+        /// The unix socket of the daemon.
+        #[arg(long, value_name = "PATH")]
+        socket: Option<PathBuf>,
+    },
+}
+
+impl Command {
+    /// The players a command acts on when `--player` is not passed.
+    fn default_selector(&self) -> PlayerSelector {
+        match self {
+            Command::List | Command::Watch { .. } => PlayerSelector::All,
+            Command::Query {
+                kind: QueryKind::List,
+                ..
+            } => PlayerSelector::All,
+            _ => PlayerSelector::Active,
+        }
+    }
+}
+
+/// Runs the parsed cli.
+pub async fn run(cli: Cli) -> CliResult<()> {
+    let selector = match &cli.command {
+        Command::Doctor {
+            player: Some(player),
+        } => player.clone(),
+        _ => cli
+            .player
+            .clone()
+            .unwrap_or_else(|| cli.command.default_selector()),
+    };
+
+    // Queries go to the daemon, so they don't need a D-Bus connection.
+    if let Command::Query { kind, socket } = cli.command {
+        let socket = socket.unwrap_or_else(daemon::default_socket);
+        return daemon::query(&socket, kind, selector, cli.priority).await;
+    }
+
+    // The monitor watches the whole bus, on a connection of its own.
+    if let Command::Monitor = cli.command {
+        return monitor::run(cli.bus).await;
+    }
+
+    let mut mpris = Mpris::new_on(cli.bus.clone()).await?;
+
+    match &cli.command {
+        Command::Watch { only } => {
+            return watch::run(&mut mpris, &selector, &cli.priority, only).await;
+        }
+        Command::Daemon { socket } => {
+            let socket = socket.clone().unwrap_or_else(daemon::default_socket);
+            return daemon::run(mpris, &socket).await;
+        }
+        _ => {}
+    }
+
+    let players = selector
+        .select(mpris.list_players().await?, &cli.priority)
+        .await;
+
+    if players.is_empty() {
+        return Err(format!("No player matches `{selector}`.").into());
+    }
+
+    // Prefix the output with the player name when there is more than one.
+    let prefixed = players.len() > 1;
+
+    for player in players {
+        let short = player.identity().short().to_string();
+        let prefix = if prefixed {
+            format!("{short}: ")
+        } else {
+            String::new()
+        };
+
+        match &cli.command {
+            Command::List => println!("{short}\t{}", player.identity().bus()),
+            Command::Status => println!("{prefix}{}", player.playback_status().await?),
+            Command::Metadata => {
+                for line in metadata_lines(&player.metadata().await?)? {
+                    println!("{prefix}{line}");
+                }
+            }
+            Command::Play => player.play().await?,
+            Command::Pause => player.pause().await?,
+            Command::PlayPause => player.play_pause().await?,
+            Command::Stop => player.stop().await?,
+            Command::Next => player.next().await?,
+            Command::Previous => player.previous().await?,
+            Command::Position { value } => position::run(&player, value.as_ref(), &prefix).await?,
+            Command::Art { save, url } => art::run(&player, save.as_deref(), *url, &prefix).await?,
+            Command::Doctor { .. } => doctor::run(&player, &prefix).await?,
+            Command::Watch { .. }
+            | Command::Daemon { .. }
+            | Command::Query { .. }
+            | Command::Monitor => {
+                unreachable!("handled above")
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Formats the metadata of the current track of a player, one field per line.
+pub fn metadata_lines(metadata: &PlayerMetadata<'_>) -> CliResult<Vec<String>> {
+    let mut lines = Vec::new();
+
+    if let Some(track_id) = metadata.track_id()? {
+        lines.push(format!("trackid: {}", track_id.as_ref()));
+    }
+
+    if let Some(title) = metadata.title()? {
+        lines.push(format!("title: {title}"));
+    }
+
+    if let Some(artists) = metadata.artists()? {
+        lines.push(format!("artists: {}", artists.join(", ")));
+    }
+
+    if let Some(album) = metadata.album()? {
+        lines.push(format!("album: {album}"));
+    }
+
+    if let Some(length) = metadata.length()? {
+        lines.push(format!("length: {}", format_duration(length)));
+    }
+
+    if let Some(art_url) = metadata.art_url()? {
+        lines.push(format!("art_url: {art_url}"));
+    }
+
+    Ok(lines)
+}
+
+/// Formats a duration as `m:ss`, or `h:mm:ss` when it is an hour or longer.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::Path;
+
+use mprizzle::MprisPlayer;
+
+use super::CliResult;
+
+/// Prints the artwork url of the current track and/or saves the artwork to a file.
+pub async fn run(
+    player: &MprisPlayer,
+    save: Option<&Path>,
+    print_url: bool,
+    prefix: &str,
+) -> CliResult<()> {
+    let metadata = player.metadata().await?;
+    let url = metadata
+        .art_url()?
+        .ok_or("The current track has no artwork.")?;
+
+    // Printing the url is the default when there is nothing to save.
+    if print_url || save.is_none() {
+        println!("{prefix}{url}");
+    }
+
+    if let Some(path) = save {
+        let art = metadata.art()?.ok_or("The current track has no artwork.")?;
+        art.save(path).await?;
+    }
+
+    Ok(())
+}
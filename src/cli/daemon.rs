@@ -0,0 +1,398 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use mprizzle::{
+    Mpris, MprisEvent, MprisPlayer, PlaybackStatus, PlayerIdentity, PlayerSnapshot, PlayerState,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    signal::unix::{SignalKind, signal},
+    sync::{mpsc, oneshot},
+};
+
+use super::{
+    CliResult, metadata_lines,
+    select::{PlayerSelector, pick_active},
+};
+
+/// What `mprizzle query` can ask the daemon for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum QueryKind {
+    /// The players.
+    List,
+
+    /// The playback status.
+    Status,
+
+    /// The metadata of the current track.
+    Metadata,
+
+    /// The position in seconds.
+    Position,
+}
+
+/// A query sent to the daemon, encoded as a single tab separated line.
+#[derive(Debug)]
+struct Request {
+    kind: QueryKind,
+    selector: PlayerSelector,
+    priority: Vec<String>,
+}
+
+impl Request {
+    fn encode(&self) -> String {
+        let kind = self.kind.to_possible_value().expect("no skipped variants");
+        format!(
+            "{}\t{}\t{}\n",
+            kind.get_name(),
+            self.selector,
+            self.priority.join(",")
+        )
+    }
+
+    fn decode(line: &str) -> Result<Self, String> {
+        let mut fields = line.trim_end_matches('\n').split('\t');
+
+        let kind = QueryKind::from_str(fields.next().unwrap_or_default(), false)?;
+        let selector = fields
+            .next()
+            .unwrap_or("active")
+            .parse()
+            .unwrap_or(PlayerSelector::Active);
+        let priority = fields
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(Self {
+            kind,
+            selector,
+            priority,
+        })
+    }
+}
+
+/// The answer to a request: the lines to print, or an error message.
+type Response = Result<Vec<String>, String>;
+
+/// The state refetched by a refresh of a player, with the number of that refresh and when it was got.
+type Refreshed = (PlayerIdentity, u64, PlayerState, Instant);
+
+/// A player and its last known state, kept up to date from the mpris events.
+struct CachedPlayer {
+    player: MprisPlayer,
+    status: Option<PlaybackStatus>,
+    metadata: Vec<String>,
+
+    /// The last known position, and when it was known.
+    position: (Duration, Instant),
+
+    /// The playback rate, the players without one play at the normal one.
+    rate: f64,
+
+    /// The number of the last refresh asked for, the answers to the older ones get dropped.
+    refresh: u64,
+}
+
+impl CachedPlayer {
+    /// Seeds the state from the snapshot the player got attached with.
+    fn new(player: MprisPlayer, snapshot: &PlayerSnapshot) -> Self {
+        Self {
+            player,
+            status: snapshot.playback_status().cloned(),
+            metadata: snapshot
+                .metadata()
+                .and_then(|metadata| metadata_lines(metadata).ok())
+                .unwrap_or_default(),
+            position: (Duration::ZERO, Instant::now()),
+            rate: 1.0,
+            refresh: 0,
+        }
+    }
+
+    /// Refetches the whole state of the player with a single call, in the background,
+    /// for the daemon to keep answering queries while a player is slow to answer.
+    fn refresh(&mut self, refreshed: &mpsc::UnboundedSender<Refreshed>) {
+        self.refresh += 1;
+
+        let player = self.player.clone();
+        let refresh = self.refresh;
+        let refreshed = refreshed.clone();
+
+        tokio::spawn(async move {
+            if let Ok(state) = player.state().await {
+                let _ = refreshed.send((player.identity().clone(), refresh, state, Instant::now()));
+            }
+        });
+    }
+
+    /// Applies the state refetched by a refresh, unless a later one was asked for since.
+    fn apply(&mut self, refresh: u64, state: PlayerState, at: Instant) {
+        if refresh != self.refresh {
+            return;
+        }
+
+        self.status = state.playback_status().cloned();
+        self.metadata = state
+            .metadata()
+            .and_then(|metadata| metadata_lines(metadata).ok())
+            .unwrap_or_default();
+        self.rate = state.rate().unwrap_or(1.0);
+
+        if let Some(position) = state.position() {
+            self.position = (position, at);
+        }
+    }
+
+    fn playing(&self) -> bool {
+        self.status == Some(PlaybackStatus::Playing)
+    }
+
+    /// The position, moved forward at the playback rate since it was known when playing.
+    fn position(&self) -> Duration {
+        let (position, at) = self.position;
+
+        match self.playing() {
+            true => position + at.elapsed().mul_f64(self.rate.max(0.0)),
+            false => position,
+        }
+    }
+}
+
+/// The default path of the daemon socket.
+pub fn default_socket() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("mprizzle.sock"),
+        None => {
+            let user = std::env::var("USER").unwrap_or_default();
+            std::env::temp_dir().join(format!("mprizzle-{user}.sock"))
+        }
+    }
+}
+
+/// Runs the daemon, answering queries from the state it keeps up to date.
+pub async fn run(mut mpris: Mpris, socket: &Path) -> CliResult<()> {
+    if socket.exists() {
+        if UnixStream::connect(socket).await.is_ok() {
+            return Err(format!("A daemon is already listening on {}.", socket.display()).into());
+        }
+
+        // A leftover from a daemon that didn't exit cleanly.
+        std::fs::remove_file(socket)?;
+    }
+
+    let listener = UnixListener::bind(socket)?;
+    let (request_tx, mut request_rx) = mpsc::channel::<(Request, oneshot::Sender<Response>)>(16);
+
+    let (refreshed_tx, mut refreshed_rx) = mpsc::unbounded_channel::<Refreshed>();
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+
+    mpris.watch();
+
+    let mut players: HashMap<PlayerIdentity, CachedPlayer> = HashMap::new();
+
+    let result = loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+            _ = sigterm.recv() => break Ok(()),
+
+            event = mpris.recv() => {
+                let event = match event {
+                    Ok(Ok(event)) => event,
+                    Ok(Err(err)) => {
+                        eprintln!("mprizzle: {err}");
+                        continue;
+                    }
+                    Err(err) => break Err(err.into()),
+                };
+
+                match event {
+                    // The snapshot has no position, a refresh gets it.
+                    MprisEvent::PlayerAttached(player, snapshot) => {
+                        let identity = player.identity().clone();
+                        let mut cached = CachedPlayer::new(player, &snapshot);
+                        cached.refresh(&refreshed_tx);
+                        players.insert(identity, cached);
+                    }
+                    MprisEvent::PlayerDetached(identity) => {
+                        players.remove(&identity);
+                    }
+                    MprisEvent::PlayerPropertiesChanged(identity, _) | MprisEvent::PlayerSeeked(identity) => {
+                        if let Some(cached) = players.get_mut(&identity) {
+                            cached.refresh(&refreshed_tx);
+                        }
+                    }
+                    MprisEvent::PlayerPosition(identity, position) => {
+                        if let Some(cached) = players.get_mut(&identity) {
+                            cached.position = (position, Instant::now());
+                        }
+                    }
+                    // Already covered by the refresh on the properties changes.
+                    MprisEvent::PlayerMetadataChanged(_, _)
+                    | MprisEvent::PlayerTrackChanged(_, _)
+                    | MprisEvent::PlayerTrackStarted(_, _)
+                    | MprisEvent::PlayerTrackFinished(_, _)
+                    | MprisEvent::PlayerTrackScrobblable(_, _)
+                    | MprisEvent::PlayerTrackEnding(_, _)
+                    | MprisEvent::PlayerPlaybackStatusChanged(_, _, _)
+                    | MprisEvent::PlayerCapabilitiesChanged(_, _)
+                    | MprisEvent::PlayerAutoPaused(_, _) => {}
+                    // Nothing the daemon answers with lives on the root or playlists interfaces.
+                    MprisEvent::PlayerRootPropertiesChanged(_, _)
+                    | MprisEvent::PlayerPlaylistChanged(_, _) => {}
+                }
+            }
+
+            Some((identity, refresh, state, at)) = refreshed_rx.recv() => {
+                if let Some(cached) = players.get_mut(&identity) {
+                    cached.apply(refresh, state, at);
+                }
+            }
+
+            Ok((stream, _)) = listener.accept() => {
+                tokio::spawn(serve(stream, request_tx.clone()));
+            }
+
+            Some((request, respond)) = request_rx.recv() => {
+                let _ = respond.send(answer(&players, &request));
+            }
+        }
+    };
+
+    let _ = std::fs::remove_file(socket);
+
+    if let Err(err) = mpris.shutdown().await {
+        eprintln!("mprizzle: {err}");
+    }
+
+    result
+}
+
+/// Reads a request from a client, and writes back the answer of the daemon.
+async fn serve(stream: UnixStream, request_tx: mpsc::Sender<(Request, oneshot::Sender<Response>)>) {
+    let (reader, mut writer) = stream.into_split();
+
+    let mut line = String::new();
+    if BufReader::new(reader).read_line(&mut line).await.is_err() {
+        return;
+    }
+
+    let response = match Request::decode(&line) {
+        Ok(request) => {
+            let (respond, response) = oneshot::channel();
+            let _ = request_tx.send((request, respond)).await;
+            response
+                .await
+                .unwrap_or_else(|_| Err("The daemon is shutting down.".into()))
+        }
+        Err(err) => Err(err),
+    };
+
+    let output = match response {
+        Ok(lines) => lines
+            .into_iter()
+            .fold("ok\n".to_string(), |output, line| output + &line + "\n"),
+        Err(err) => format!("error {err}\n"),
+    };
+
+    let _ = writer.write_all(output.as_bytes()).await;
+}
+
+/// Answers a request from the cached state.
+fn answer(players: &HashMap<PlayerIdentity, CachedPlayer>, request: &Request) -> Response {
+    // Sorted, so the output doesn't depend on the hash map order.
+    let mut candidates: Vec<&CachedPlayer> = players.values().collect();
+    candidates.sort_by(|a, b| a.player.identity().bus().cmp(b.player.identity().bus()));
+
+    let selected: Vec<&CachedPlayer> = match &request.selector {
+        PlayerSelector::Active => {
+            let candidates = candidates
+                .into_iter()
+                .map(|cached| (cached.playing(), cached))
+                .collect();
+            pick_active(candidates, &request.priority, |cached| {
+                cached.player.identity()
+            })
+            .into_iter()
+            .collect()
+        }
+        selector => candidates
+            .into_iter()
+            .filter(|cached| selector.matches(cached.player.identity()))
+            .collect(),
+    };
+
+    if selected.is_empty() {
+        return Err(format!("No player matches `{}`.", request.selector));
+    }
+
+    let prefixed = selected.len() > 1;
+    let mut lines = Vec::new();
+
+    for cached in selected {
+        let identity = cached.player.identity();
+        let prefix = match prefixed {
+            true => format!("{}: ", identity.short()),
+            false => String::new(),
+        };
+
+        match request.kind {
+            QueryKind::List => lines.push(format!("{}\t{}", identity.short(), identity.bus())),
+            QueryKind::Status => match &cached.status {
+                Some(status) => lines.push(format!("{prefix}{status}")),
+                None => lines.push(format!("{prefix}Unknown")),
+            },
+            QueryKind::Metadata => {
+                lines.extend(cached.metadata.iter().map(|line| format!("{prefix}{line}")))
+            }
+            QueryKind::Position => {
+                lines.push(format!("{prefix}{}", cached.position().as_secs_f64()))
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Sends a query to the daemon and prints its answer.
+pub async fn query(
+    socket: &Path,
+    kind: QueryKind,
+    selector: PlayerSelector,
+    priority: Vec<String>,
+) -> CliResult<()> {
+    let mut stream = UnixStream::connect(socket).await.map_err(|err| {
+        format!(
+            "Failed to connect to the daemon at {}: {err}",
+            socket.display()
+        )
+    })?;
+
+    let request = Request {
+        kind,
+        selector,
+        priority,
+    };
+    stream.write_all(request.encode().as_bytes()).await?;
+
+    let mut output = String::new();
+    stream.read_to_string(&mut output).await?;
+
+    let (status, lines) = output.split_once('\n').unwrap_or((&output, ""));
+    if let Some(err) = status.strip_prefix("error ") {
+        return Err(err.into());
+    }
+
+    print!("{lines}");
+
+    Ok(())
+}
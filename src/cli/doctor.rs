@@ -0,0 +1,30 @@
+use mprizzle::{CheckStatus, MprisPlayer};
+
+use super::CliResult;
+
+/// Prints how the player does against the MPRIS spec, one check per line.
+pub async fn run(player: &MprisPlayer, prefix: &str) -> CliResult<()> {
+    let report = player.check_compliance().await?;
+
+    for check in report.checks() {
+        match check.detail() {
+            Some(detail) => println!("{prefix}{}\t{}: {detail}", check.status(), check.name()),
+            None => println!("{prefix}{}\t{}", check.status(), check.name()),
+        }
+    }
+
+    let warnings = report
+        .checks()
+        .iter()
+        .filter(|check| check.status() == CheckStatus::Warn)
+        .count();
+
+    let failures = report.failures().count();
+    println!("{prefix}{failures} failed, {warnings} warnings");
+
+    if !report.is_compliant() {
+        return Err(format!("`{}` is not MPRIS compliant.", report.bus()).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use futures::StreamExt;
+use mprizzle::{BusKind, MprisMonitor};
+
+use super::CliResult;
+
+/// Prints the mpris traffic on the bus as it happens, one message per line.
+pub async fn run(bus: BusKind) -> CliResult<()> {
+    let mut monitor = MprisMonitor::new_on(bus).await?;
+
+    while let Some(message) = monitor.next().await {
+        let message = message?;
+
+        let sender = message.sender().unwrap_or_else(|| "?".into());
+        let destination = message.destination().unwrap_or_else(|| "*".into());
+        let name = match (message.interface(), message.member()) {
+            (Some(interface), Some(member)) => format!("{interface}.{member}"),
+            (None, Some(member)) => member,
+            _ => String::new(),
+        };
+
+        println!(
+            "{}\t{sender} -> {destination}\t{name}\t{}",
+            message.kind(),
+            message.body()
+        );
+    }
+
+    Ok(())
+}
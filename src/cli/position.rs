@@ -0,0 +1,74 @@
+use std::{str::FromStr, time::Duration};
+
+use mprizzle::MprisPlayer;
+
+use super::CliResult;
+
+/// A position argument, as passed to `mprizzle position`.
+#[derive(Debug, Clone)]
+pub enum PositionArg {
+    /// Seek to an absolute position, e.g. `30`.
+    Absolute(Duration),
+
+    /// Seek forward relative to the current position, e.g. `5+`.
+    Forward(Duration),
+
+    /// Seek backward relative to the current position, e.g. `5-`.
+    Backward(Duration),
+
+    /// Seek to a percentage of the track length, e.g. `50%`.
+    Percent(f64),
+}
+
+impl FromStr for PositionArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let secs = |s: &str| {
+            s.parse::<f64>()
+                .ok()
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .ok_or_else(|| format!("`{s}` is not a valid amount of seconds"))
+        };
+
+        if let Some(offset) = s.strip_suffix('+') {
+            Ok(PositionArg::Forward(secs(offset)?))
+        } else if let Some(offset) = s.strip_suffix('-') {
+            Ok(PositionArg::Backward(secs(offset)?))
+        } else if let Some(percent) = s.strip_suffix('%') {
+            match percent.parse::<f64>() {
+                Ok(percent) if (0.0..=100.0).contains(&percent) => {
+                    Ok(PositionArg::Percent(percent))
+                }
+                _ => Err(format!("`{percent}` is not a percentage between 0 and 100")),
+            }
+        } else {
+            Ok(PositionArg::Absolute(secs(s)?))
+        }
+    }
+}
+
+/// Prints the position of the player in seconds, or seeks it when an argument is passed.
+pub async fn run(player: &MprisPlayer, arg: Option<&PositionArg>, prefix: &str) -> CliResult<()> {
+    let Some(arg) = arg else {
+        println!("{prefix}{}", player.position().await?.as_secs_f64());
+        return Ok(());
+    };
+
+    match arg {
+        PositionArg::Forward(offset) => player.seek(offset.as_micros() as i64).await?,
+        PositionArg::Backward(offset) => player.seek(-(offset.as_micros() as i64)).await?,
+        PositionArg::Absolute(position) => player.seek_to(*position).await?,
+        PositionArg::Percent(percent) => {
+            let length = player
+                .metadata()
+                .await?
+                .length()?
+                .ok_or("The current track has no length.")?;
+
+            player.seek_to(length.mul_f64(percent / 100.0)).await?;
+        }
+    }
+
+    Ok(())
+}
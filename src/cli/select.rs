@@ -0,0 +1,99 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use mprizzle::{MprisPlayer, PlaybackStatus, PlayerIdentity};
+
+/// Which players a command should act on, as passed to `--player`.
+#[derive(Debug, Clone)]
+pub enum PlayerSelector {
+    /// The single most relevant player, picked using the priority list.
+    Active,
+
+    /// Every player on the bus.
+    All,
+
+    /// The players whose short name, name or bus name matches a glob pattern.
+    Pattern(String),
+}
+
+impl PlayerSelector {
+    /// Returns `true` if the identity matches this selector.
+    ///
+    /// `Active` can't be decided from an identity alone, so it matches everything.
+    pub fn matches(&self, identity: &PlayerIdentity) -> bool {
+        match self {
+            PlayerSelector::Active | PlayerSelector::All => true,
+            PlayerSelector::Pattern(pattern) => identity.matches_glob(pattern),
+        }
+    }
+
+    /// Narrows down the players to the ones this selector targets.
+    pub async fn select(&self, players: Vec<MprisPlayer>, priority: &[String]) -> Vec<MprisPlayer> {
+        match self {
+            PlayerSelector::Active => active_player(players, priority).await.into_iter().collect(),
+            _ => players
+                .into_iter()
+                .filter(|player| self.matches(player.identity()))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for PlayerSelector {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "active" => PlayerSelector::Active,
+            "all" => PlayerSelector::All,
+            pattern => PlayerSelector::Pattern(pattern.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for PlayerSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerSelector::Active => write!(f, "active"),
+            PlayerSelector::All => write!(f, "all"),
+            PlayerSelector::Pattern(pattern) => write!(f, "{pattern}"),
+        }
+    }
+}
+
+/// Picks the active player, see [`pick_active`].
+async fn active_player(players: Vec<MprisPlayer>, priority: &[String]) -> Option<MprisPlayer> {
+    let mut candidates = Vec::with_capacity(players.len());
+
+    for player in players {
+        let playing = matches!(player.playback_status().await, Ok(PlaybackStatus::Playing));
+        candidates.push((playing, player));
+    }
+
+    pick_active(candidates, priority, |player| player.identity())
+}
+
+/// Picks the active candidate out of `(playing, candidate)` pairs.
+///
+/// Playing players win over the rest, then players earlier in the priority
+/// list win over later ones, and players not in the list come last.
+pub fn pick_active<T>(
+    candidates: Vec<(bool, T)>,
+    priority: &[String],
+    identity: impl Fn(&T) -> &PlayerIdentity,
+) -> Option<T> {
+    let mut ranked: Vec<(bool, usize, T)> = candidates
+        .into_iter()
+        .map(|(playing, candidate)| {
+            let rank = priority
+                .iter()
+                .position(|pattern| identity(&candidate).matches_glob(pattern))
+                .unwrap_or(priority.len());
+
+            (!playing, rank, candidate)
+        })
+        .collect();
+
+    // Stable sort, so ties keep the bus order.
+    ranked.sort_by_key(|(not_playing, rank, _)| (*not_playing, *rank));
+    ranked.into_iter().next().map(|(_, _, candidate)| candidate)
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use mprizzle::{
+    Capabilities, EventFilter, EventKind, Mpris, MprisEvent, PlayerIdentity, PlayerMetadata,
+    PropertiesDiff,
+};
+use zvariant::OwnedValue;
+
+use super::{CliResult, format_duration, select::PlayerSelector};
+
+/// What `mprizzle watch --only` can narrow the output down to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WatchOnly {
+    /// Players appearing.
+    Attached,
+
+    /// Players disappearing.
+    Detached,
+
+    /// Any property change.
+    Properties,
+
+    /// Any root property change, like the identity or fullscreen.
+    Root,
+
+    /// Playlist changes, like renames.
+    Playlist,
+
+    /// Seeks.
+    Seeked,
+
+    /// Position ticks while playing.
+    Position,
+
+    /// Playback status changes.
+    Status,
+
+    /// Track changes.
+    Metadata,
+
+    /// Volume changes.
+    Volume,
+
+    /// Loop status changes.
+    Loop,
+
+    /// Shuffle changes.
+    Shuffle,
+
+    /// Playback rate changes.
+    Rate,
+}
+
+impl WatchOnly {
+    /// The event kind this maps to, if it's not a single property.
+    fn kind(self) -> Option<EventKind> {
+        match self {
+            WatchOnly::Attached => Some(EventKind::PlayerAttached),
+            WatchOnly::Detached => Some(EventKind::PlayerDetached),
+            WatchOnly::Properties => Some(EventKind::PlayerPropertiesChanged),
+            WatchOnly::Root => Some(EventKind::PlayerRootPropertiesChanged),
+            WatchOnly::Playlist => Some(EventKind::PlayerPlaylistChanged),
+            WatchOnly::Seeked => Some(EventKind::PlayerSeeked),
+            WatchOnly::Position => Some(EventKind::PlayerPosition),
+            _ => None,
+        }
+    }
+
+    /// The mpris property this maps to, if it's a single property.
+    fn property(self) -> Option<&'static str> {
+        match self {
+            WatchOnly::Status => Some("PlaybackStatus"),
+            WatchOnly::Metadata => Some("Metadata"),
+            WatchOnly::Volume => Some("Volume"),
+            WatchOnly::Loop => Some("LoopStatus"),
+            WatchOnly::Shuffle => Some("Shuffle"),
+            WatchOnly::Rate => Some("Rate"),
+            _ => None,
+        }
+    }
+}
+
+/// Prints the events of the selected players as they happen, one per line.
+pub async fn run(
+    mpris: &mut Mpris,
+    selector: &PlayerSelector,
+    priority: &[String],
+    only: &[WatchOnly],
+) -> CliResult<()> {
+    // The active player can't be decided from events alone, so resolve it up front.
+    let active = match selector {
+        PlayerSelector::Active => {
+            let players = selector.select(mpris.list_players().await?, priority).await;
+            let Some(player) = players.first() else {
+                return Err("No active player to watch.".into());
+            };
+
+            Some(player.identity().clone())
+        }
+        _ => None,
+    };
+
+    let wanted = |identity: &PlayerIdentity| match &active {
+        Some(active) => active == identity,
+        None => selector.matches(identity),
+    };
+
+    let shown = |kind: EventKind| only.is_empty() || only.iter().any(|o| o.kind() == Some(kind));
+    let properties: Vec<WatchOnly> = match shown(EventKind::PlayerPropertiesChanged) {
+        true => Vec::new(),
+        false => only
+            .iter()
+            .copied()
+            .filter(|o| o.property().is_some())
+            .collect(),
+    };
+
+    mpris.watch_filtered(event_filter(only));
+
+    loop {
+        // An error of a single player doesn't stop the others from being watched.
+        let event = match mpris.recv().await? {
+            Ok(event) => event,
+            Err(err) => {
+                eprintln!("mprizzle: {err}");
+                continue;
+            }
+        };
+
+        let identity = event.identity().clone();
+        let kind = event.kind();
+
+        if !wanted(&identity) {
+            continue;
+        }
+
+        // The property changes carry the values of the watched properties.
+        if !(shown(kind) || kind == EventKind::PlayerPropertiesChanged) {
+            continue;
+        }
+
+        match event_lines(event, &properties) {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{} {line}", identity.short());
+                }
+            }
+            Err(err) => eprintln!("mprizzle: {}: {err}", identity.short()),
+        }
+    }
+}
+
+/// Formats the event, one line each for the watched properties.
+fn event_lines(event: MprisEvent, properties: &[WatchOnly]) -> CliResult<Vec<String>> {
+    let lines = match event {
+        MprisEvent::PlayerAttached(..) => vec!["attached".to_string()],
+        MprisEvent::PlayerDetached(_) => vec!["detached".to_string()],
+        MprisEvent::PlayerPropertiesChanged(_, diff) => match properties.is_empty() {
+            true => vec!["properties".to_string()],
+            false => property_lines(&diff, properties)?,
+        },
+        MprisEvent::PlayerMetadataChanged(_, diff) => vec![format!(
+            "metadata-changed {}",
+            diff.changed_fields().join(",")
+        )],
+        MprisEvent::PlayerTrackChanged(_, metadata) => {
+            vec![format!("track {}", track_name(&metadata)?)]
+        }
+        MprisEvent::PlayerTrackStarted(_, metadata) => {
+            vec![format!("track-started {}", track_name(&metadata)?)]
+        }
+        MprisEvent::PlayerTrackFinished(_, metadata) => {
+            vec![format!("track-finished {}", track_name(&metadata)?)]
+        }
+        MprisEvent::PlayerTrackEnding(_, remaining) => {
+            vec![format!("track-ending {}", format_duration(remaining))]
+        }
+        MprisEvent::PlayerTrackScrobblable(_, metadata) => {
+            vec![format!("track-scrobblable {}", track_name(&metadata)?)]
+        }
+        MprisEvent::PlayerPlaybackStatusChanged(_, old, new) => {
+            vec![format!("status-changed {old} -> {new}")]
+        }
+        MprisEvent::PlayerCapabilitiesChanged(_, capabilities) => {
+            vec![format!("capabilities {}", capability_names(&capabilities))]
+        }
+        MprisEvent::PlayerRootPropertiesChanged(_, _) => {
+            vec!["root-properties".to_string()]
+        }
+        MprisEvent::PlayerPlaylistChanged(_, playlist) => {
+            vec![format!("playlist {}", playlist.name())]
+        }
+        MprisEvent::PlayerSeeked(_) => vec!["seeked".to_string()],
+        MprisEvent::PlayerPosition(_, position) => {
+            vec![format!("position {}", format_duration(position))]
+        }
+        MprisEvent::PlayerAutoPaused(_, playing) => {
+            vec![format!("auto-paused {}", playing.short())]
+        }
+    };
+
+    Ok(lines)
+}
+
+/// Builds the library event filter for `--only`.
+fn event_filter(only: &[WatchOnly]) -> EventFilter {
+    if only.is_empty() {
+        return EventFilter::all();
+    }
+
+    let kinds = only.iter().filter_map(|o| o.kind());
+
+    let filter = EventFilter::all().kinds(kinds);
+
+    match only.contains(&WatchOnly::Properties) {
+        true => filter,
+        false => filter.properties(only.iter().filter_map(|o| o.property())),
+    }
+}
+
+/// Formats the track as `artists - title`.
+fn track_name(metadata: &PlayerMetadata) -> CliResult<String> {
+    let artists = metadata.artists()?.unwrap_or_default().join(", ");
+    let title = metadata.title()?.unwrap_or_default();
+
+    Ok(format!("{artists} - {title}"))
+}
+
+/// The names of what can be done with the player, like `play,pause,next`.
+fn capability_names(capabilities: &Capabilities) -> String {
+    [
+        (capabilities.can_play(), "play"),
+        (capabilities.can_pause(), "pause"),
+        (capabilities.can_seek(), "seek"),
+        (capabilities.can_go_next(), "next"),
+        (capabilities.can_go_previous(), "previous"),
+        (capabilities.can_control(), "control"),
+    ]
+    .into_iter()
+    .filter_map(|(can, name)| can.then_some(name))
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Formats the watched properties that changed, with the values the event carries, one line each.
+fn property_lines(diff: &PropertiesDiff, properties: &[WatchOnly]) -> CliResult<Vec<String>> {
+    let mut lines = Vec::with_capacity(properties.len());
+
+    for property in properties {
+        // Either sent along with the change, or refetched before the event got sent out.
+        let Some(value) = property
+            .property()
+            .and_then(|name| diff.changed().get(name).or_else(|| diff.get(name)))
+        else {
+            continue;
+        };
+
+        let line = match property {
+            WatchOnly::Status => format!("status {}", <&str>::try_from(value)?),
+            WatchOnly::Metadata => format!("metadata {}", track_name(&metadata(value)?)?),
+            WatchOnly::Volume => format!("volume {}", f64::try_from(value)?),
+            WatchOnly::Loop => format!("loop {}", <&str>::try_from(value)?),
+            WatchOnly::Shuffle => format!("shuffle {}", bool::try_from(value)?),
+            WatchOnly::Rate => format!("rate {}", f64::try_from(value)?),
+            _ => continue,
+        };
+
+        lines.push(line);
+    }
+
+    Ok(lines)
+}
+
+/// Reads the metadata out of the value of the `Metadata` property.
+fn metadata(value: &OwnedValue) -> CliResult<PlayerMetadata<'static>> {
+    let metadata = HashMap::<String, OwnedValue>::try_from(value.try_clone()?)?;
+
+    Ok(PlayerMetadata::new(
+        metadata
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect(),
+    ))
+}
@@ -0,0 +1,347 @@
+use std::{collections::HashMap, fmt};
+
+use zbus::{
+    fdo::{IntrospectableProxy, PropertiesProxy},
+    names::InterfaceName,
+};
+use zvariant::OwnedValue;
+
+use crate::{
+    interfaces::interface_section,
+    proxies::{DBUS_MPRIS_INTERFACE_NAME, DBUS_MPRIS_PLAYER_INTERFACE_NAME},
+};
+
+/// The properties of the root interface: the name, the signature and whether the spec requires it.
+pub(crate) const ROOT_PROPERTIES: [(&str, &str, bool); 9] = [
+    ("CanQuit", "b", true),
+    ("Fullscreen", "b", false),
+    ("CanSetFullscreen", "b", false),
+    ("CanRaise", "b", true),
+    ("HasTrackList", "b", true),
+    ("Identity", "s", true),
+    ("DesktopEntry", "s", false),
+    ("SupportedUriSchemes", "as", true),
+    ("SupportedMimeTypes", "as", true),
+];
+
+/// The properties of the player interface: the name, the signature and whether the spec requires it.
+pub(crate) const PLAYER_PROPERTIES: [(&str, &str, bool); 15] = [
+    ("PlaybackStatus", "s", true),
+    ("LoopStatus", "s", false),
+    ("Rate", "d", true),
+    ("Shuffle", "b", false),
+    ("Metadata", "a{sv}", true),
+    ("Volume", "d", true),
+    ("Position", "x", true),
+    ("MinimumRate", "d", true),
+    ("MaximumRate", "d", true),
+    ("CanGoNext", "b", true),
+    ("CanGoPrevious", "b", true),
+    ("CanPlay", "b", true),
+    ("CanPause", "b", true),
+    ("CanSeek", "b", true),
+    ("CanControl", "b", true),
+];
+
+/// The outcome of a single compliance check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The player does what the spec says.
+    Pass,
+
+    /// The player leaves out something optional, or does something clients have to work around.
+    Warn,
+
+    /// The player breaks the spec.
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Warn => write!(f, "warn"),
+            CheckStatus::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// A single check of a [`ComplianceReport`].
+#[derive(Debug, Clone)]
+pub struct ComplianceCheck {
+    /// What got checked (e.g., `Player.PlaybackStatus`).
+    name: String,
+
+    /// How the player did.
+    status: CheckStatus,
+
+    /// What went wrong, if anything did.
+    detail: Option<String>,
+}
+
+impl ComplianceCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail,
+        }
+    }
+
+    /// Gets what got checked.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets how the player did.
+    pub fn status(&self) -> CheckStatus {
+        self.status
+    }
+
+    /// Gets what went wrong, if anything did.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+}
+
+/// The report of probing a player against the MPRIS spec, see [`crate::MprisPlayer::check_compliance`].
+///
+/// Covers the presence and types of the root and player properties, a few of their values,
+/// `GetAll` and the signals declared by the player. The player doesn't get controlled,
+/// so whether the signals are actually emitted isn't checked.
+#[derive(Debug, Clone)]
+pub struct ComplianceReport {
+    /// The bus name of the probed player.
+    bus: String,
+
+    /// The checks, in the order they were made.
+    checks: Vec<ComplianceCheck>,
+}
+
+impl ComplianceReport {
+    /// Probes a player through its properties and introspection data.
+    pub(crate) async fn probe(
+        bus: &str,
+        properties_proxy: &PropertiesProxy<'_>,
+        introspectable_proxy: &IntrospectableProxy<'_>,
+    ) -> Self {
+        let mut report = Self {
+            bus: bus.to_string(),
+            checks: Vec::new(),
+        };
+
+        report
+            .check_interface(
+                properties_proxy,
+                "Root",
+                DBUS_MPRIS_INTERFACE_NAME,
+                &ROOT_PROPERTIES,
+            )
+            .await;
+
+        let player_properties = report
+            .check_interface(
+                properties_proxy,
+                "Player",
+                DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+                &PLAYER_PROPERTIES,
+            )
+            .await;
+
+        report.check_values(&player_properties);
+
+        match introspectable_proxy.introspect().await {
+            Ok(xml) => {
+                report.check_signal(&xml, DBUS_MPRIS_PLAYER_INTERFACE_NAME, "Seeked");
+                report.check_signal(&xml, "org.freedesktop.DBus.Properties", "PropertiesChanged");
+            }
+            Err(err) => report.push(
+                "Introspect",
+                CheckStatus::Warn,
+                Some(format!("Failed to introspect: {err}")),
+            ),
+        }
+
+        report
+    }
+
+    /// Checks the properties of an interface, returning the ones the player has.
+    async fn check_interface(
+        &mut self,
+        properties_proxy: &PropertiesProxy<'_>,
+        prefix: &str,
+        interface: &'static str,
+        expected: &[(&str, &str, bool)],
+    ) -> HashMap<String, OwnedValue> {
+        let interface_name = InterfaceName::from_static_str_unchecked(interface);
+
+        // GetAll is the first thing most clients call.
+        let mut properties = match properties_proxy.get_all(interface_name.clone()).await {
+            Ok(properties) if !properties.is_empty() => {
+                self.push(format!("{prefix}.GetAll"), CheckStatus::Pass, None);
+                properties
+            }
+            Ok(_) => {
+                self.push(
+                    format!("{prefix}.GetAll"),
+                    CheckStatus::Fail,
+                    Some("Returned no properties".into()),
+                );
+                HashMap::new()
+            }
+            Err(err) => {
+                self.push(
+                    format!("{prefix}.GetAll"),
+                    CheckStatus::Fail,
+                    Some(err.to_string()),
+                );
+                HashMap::new()
+            }
+        };
+
+        for (property, signature, required) in expected {
+            let name = format!("{prefix}.{property}");
+
+            // Falls back to getting the ones GetAll left out one by one.
+            if !properties.contains_key(*property)
+                && let Ok(value) = properties_proxy.get(interface_name.clone(), property).await
+            {
+                properties.insert(property.to_string(), value);
+            }
+
+            let Some(value) = properties.get(*property) else {
+                let status = if *required {
+                    CheckStatus::Fail
+                } else {
+                    CheckStatus::Warn
+                };
+
+                self.push(name, status, Some("Missing".into()));
+                continue;
+            };
+
+            let got = value.value_signature().to_string();
+            if got == *signature {
+                self.push(name, CheckStatus::Pass, None);
+            } else {
+                self.push(
+                    name,
+                    CheckStatus::Fail,
+                    Some(format!("Expected type `{signature}`, got `{got}`")),
+                );
+            }
+        }
+
+        properties
+    }
+
+    /// Checks the values of the player properties the spec restricts.
+    fn check_values(&mut self, properties: &HashMap<String, OwnedValue>) {
+        if let Some(Ok(status)) = properties
+            .get("PlaybackStatus")
+            .map(|status| <&str>::try_from(&**status))
+            && !matches!(status, "Playing" | "Paused" | "Stopped")
+        {
+            self.push(
+                "Player.PlaybackStatus value",
+                CheckStatus::Fail,
+                Some(format!("`{status}` is not Playing, Paused or Stopped")),
+            );
+        }
+
+        if let Some(Ok(status)) = properties
+            .get("LoopStatus")
+            .map(|status| <&str>::try_from(&**status))
+            && !matches!(status, "None" | "Track" | "Playlist")
+        {
+            self.push(
+                "Player.LoopStatus value",
+                CheckStatus::Fail,
+                Some(format!("`{status}` is not None, Track or Playlist")),
+            );
+        }
+
+        let Some(Ok(metadata)) = properties.get("Metadata").map(|metadata| {
+            metadata
+                .try_clone()
+                .and_then(HashMap::<String, OwnedValue>::try_from)
+        }) else {
+            return;
+        };
+
+        // The spec wants an object path, a lot of players send a string.
+        match metadata.get("mpris:trackid").map(|track_id| &**track_id) {
+            Some(zvariant::Value::ObjectPath(_)) => {
+                self.push("Metadata.mpris:trackid", CheckStatus::Pass, None)
+            }
+            Some(track_id) => self.push(
+                "Metadata.mpris:trackid",
+                CheckStatus::Fail,
+                Some(format!(
+                    "Expected type `o`, got `{}`",
+                    track_id.value_signature()
+                )),
+            ),
+            None => self.push(
+                "Metadata.mpris:trackid",
+                CheckStatus::Warn,
+                Some("Missing, seeking with SetPosition won't work".into()),
+            ),
+        }
+
+        if let Some(length) = metadata.get("mpris:length")
+            && !matches!(&**length, zvariant::Value::I64(_))
+        {
+            self.push(
+                "Metadata.mpris:length",
+                CheckStatus::Warn,
+                Some(format!(
+                    "Expected type `x`, got `{}`",
+                    length.value_signature()
+                )),
+            );
+        }
+    }
+
+    /// Checks that a signal is declared in the introspection data of an interface.
+    fn check_signal(&mut self, xml: &str, interface: &str, signal: &str) {
+        let declared = interface_section(xml, interface)
+            .is_some_and(|section| section.contains(&format!("<signal name=\"{signal}\"")));
+
+        if declared {
+            self.push(format!("Signal.{signal}"), CheckStatus::Pass, None);
+        } else {
+            self.push(
+                format!("Signal.{signal}"),
+                CheckStatus::Warn,
+                Some(format!("Not declared on {interface}")),
+            );
+        }
+    }
+
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, detail: Option<String>) {
+        self.checks.push(ComplianceCheck::new(name, status, detail));
+    }
+
+    /// Gets the bus name of the probed player.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
+    /// Gets every check, in the order they were made.
+    pub fn checks(&self) -> &[ComplianceCheck] {
+        &self.checks
+    }
+
+    /// Iterates over the checks the player failed.
+    pub fn failures(&self) -> impl Iterator<Item = &ComplianceCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Returns `true` if the player didn't fail any check.
+    pub fn is_compliant(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
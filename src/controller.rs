@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use futures::future;
+use tokio::sync::watch;
+
+use crate::{
+    EventCapacity, Mpris, MprisError, MprisPlayer, MprisResult, NowPlaying, OverflowPolicy,
+    PlayerIdentity, SharedPlayers,
+};
+
+/// Controls the active player, for the hotkey daemons and the like.
+///
+/// Watches the players to follow the active one, see [`Mpris::now_playing`], and sends each
+/// call to it. The active player is known once the watch attached the players, so shortly
+/// after creating the controller rather than right away.
+///
+/// The controller doesn't receive the events, only the last one gets queued up for
+/// [`MprisController::mpris`]. The subscriptions still get every event, see [`Mpris::subscribe`].
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::MprisController;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let controller = MprisController::new().await?;
+///
+///     controller.play_pause().await?;
+///
+///     if let Some(now_playing) = controller.now_playing() {
+///         println!("{}: {:?}", now_playing.identity().short(), now_playing.status());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MprisController {
+    mpris: Mpris,
+    now_playing: watch::Receiver<Option<NowPlaying>>,
+}
+
+impl MprisController {
+    /// Creates a controller on the session bus.
+    pub async fn new() -> MprisResult<Self> {
+        Ok(Self::from_mpris(Mpris::new().await?))
+    }
+
+    /// Creates a controller on top of a connection set up already, see [`Mpris::builder`].
+    ///
+    /// Starts watching, bounding the event channel to a single event as nothing receives them.
+    pub fn from_mpris(mpris: Mpris) -> Self {
+        mpris.update_options(|options| {
+            options.with_event_capacity(Some(EventCapacity::new(1, OverflowPolicy::DropOldest)))
+        });
+
+        let now_playing = mpris.now_playing();
+        mpris.watch();
+
+        Self { mpris, now_playing }
+    }
+
+    /// Gets the underlying connection, for the rest of the API.
+    pub fn mpris(&self) -> &Mpris {
+        &self.mpris
+    }
+
+    /// Gets what the active player is at, None without any player.
+    pub fn now_playing(&self) -> Option<NowPlaying> {
+        self.now_playing.borrow().clone()
+    }
+
+    /// Gets the active player, None without any player.
+    pub async fn active_player(&self) -> Option<MprisPlayer> {
+        let identity = self.now_playing.borrow().as_ref()?.identity().clone();
+        let players = self.mpris.players();
+        let players = players.lock().await;
+
+        players
+            .get(&identity)
+            .map(|player| MprisPlayer::clone(player))
+    }
+
+    /// Starts or resumes playback on the active player.
+    pub async fn play(&self) -> MprisResult<()> {
+        self.require_active().await?.play().await
+    }
+
+    /// Pauses playback on the active player.
+    pub async fn pause(&self) -> MprisResult<()> {
+        self.require_active().await?.pause().await
+    }
+
+    /// Toggles between play and pause on the active player.
+    pub async fn play_pause(&self) -> MprisResult<()> {
+        self.require_active().await?.play_pause().await
+    }
+
+    /// Stops playback on the active player.
+    pub async fn stop(&self) -> MprisResult<()> {
+        self.require_active().await?.stop().await
+    }
+
+    /// Skips to the next track on the active player.
+    pub async fn next(&self) -> MprisResult<()> {
+        self.require_active().await?.next().await
+    }
+
+    /// Skips to the previous track on the active player.
+    pub async fn previous(&self) -> MprisResult<()> {
+        self.require_active().await?.previous().await
+    }
+
+    /// Gets the active player, erroring with [`MprisError::NoActivePlayer`] without any.
+    async fn require_active(&self) -> MprisResult<MprisPlayer> {
+        self.active_player().await.ok_or(MprisError::NoActivePlayer)
+    }
+}
+
+/// Sends each call to every attached player at once, as returned by [`Mpris::all`].
+///
+/// The players are the ones in [`Mpris::players`] at the time of the call, so the watch has to
+/// be running. Each call gives the result of every player, one failing doesn't stop the others.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::Mpris;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mpris = Mpris::new().await?;
+///     mpris.watch();
+///
+///     for (identity, result) in mpris.all().pause().await {
+///         if let Err(err) = result {
+///             eprintln!("{}: {err}", identity.short());
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AllPlayers {
+    players: SharedPlayers,
+}
+
+impl AllPlayers {
+    pub(crate) fn new(players: SharedPlayers) -> Self {
+        Self { players }
+    }
+
+    /// Starts or resumes playback on every player.
+    pub async fn play(&self) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.play().await })
+            .await
+    }
+
+    /// Pauses playback on every player.
+    pub async fn pause(&self) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.pause().await })
+            .await
+    }
+
+    /// Stops playback on every player.
+    pub async fn stop(&self) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.stop().await })
+            .await
+    }
+
+    /// Skips to the next track on every player.
+    pub async fn next(&self) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.next().await })
+            .await
+    }
+
+    /// Skips to the previous track on every player.
+    pub async fn previous(&self) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.previous().await })
+            .await
+    }
+
+    /// Sets the volume of every player, `0.0` muting them.
+    pub async fn set_volume(&self, volume: f64) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.set_volume(volume).await })
+            .await
+    }
+
+    /// Turns shuffle on or off on every player.
+    pub async fn set_shuffle(&self, shuffle: bool) -> HashMap<PlayerIdentity, MprisResult<()>> {
+        self.fan_out(|player| async move { player.set_shuffle(shuffle).await })
+            .await
+    }
+
+    /// Runs the command on a copy of every player concurrently, gathering the results.
+    async fn fan_out<F>(
+        &self,
+        command: impl Fn(MprisPlayer) -> F,
+    ) -> HashMap<PlayerIdentity, MprisResult<()>>
+    where
+        F: Future<Output = MprisResult<()>>,
+    {
+        // Copied out, so the registry isn't locked while the players answer.
+        let players: Vec<MprisPlayer> = self
+            .players
+            .lock()
+            .await
+            .values()
+            .map(|player| MprisPlayer::clone(player))
+            .collect();
+
+        let identities = players.iter().map(|player| player.identity().clone());
+        let results = future::join_all(players.iter().cloned().map(command)).await;
+
+        identities.zip(results).collect()
+    }
+}
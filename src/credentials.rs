@@ -0,0 +1,110 @@
+use zbus::fdo::ConnectionCredentials;
+
+/// Who owns the bus of a [`crate::MprisPlayer`], as told by the bus daemon.
+///
+/// On systemd, the cgroup and unit name are read from `/proc` too,
+/// which is what tells e.g. a Flatpak Spotify apart from a native one.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::Mpris;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mpris = Mpris::new().await?;
+///
+///     for player in mpris.list_players().await? {
+///         let credentials = player.credentials().await?;
+///
+///         match credentials.flatpak_app_id() {
+///             Some(app_id) => println!("{}: flatpak {app_id}", player.identity().short()),
+///             None => println!("{}: pid {:?}", player.identity().short(), credentials.pid()),
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerCredentials {
+    /// The unix user id of the owner process.
+    uid: Option<u32>,
+
+    /// The process id of the owner process.
+    pid: Option<u32>,
+
+    /// The cgroup path of the owner process.
+    cgroup: Option<String>,
+
+    /// The systemd unit the owner process runs in.
+    unit: Option<String>,
+}
+
+impl PlayerCredentials {
+    /// Creates the credentials from the ones returned by the bus daemon,
+    /// looking up the cgroup of the process if there is one.
+    pub(crate) fn new(credentials: ConnectionCredentials) -> Self {
+        let uid = credentials.unix_user_id();
+        let pid = credentials.process_id();
+
+        let cgroup = pid.and_then(read_cgroup);
+        let unit = cgroup.as_deref().and_then(unit_from_cgroup);
+
+        Self {
+            uid,
+            pid,
+            cgroup,
+            unit,
+        }
+    }
+
+    /// Gets the unix user id.
+    pub fn uid(&self) -> Option<u32> {
+        self.uid
+    }
+
+    /// Gets the process id.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// Gets the cgroup path (e.g., `/user.slice/.../app-flatpak-com.spotify.Client-1234.scope`).
+    pub fn cgroup(&self) -> Option<&str> {
+        self.cgroup.as_deref()
+    }
+
+    /// Gets the systemd unit name (e.g., `app-flatpak-com.spotify.Client-1234.scope`).
+    pub fn unit(&self) -> Option<&str> {
+        self.unit.as_deref()
+    }
+
+    /// Gets the Flatpak app id (e.g., `com.spotify.Client`), if the player runs in a Flatpak sandbox.
+    pub fn flatpak_app_id(&self) -> Option<&str> {
+        let unit = self.unit()?.strip_suffix(".scope")?;
+        let app = unit.strip_prefix("app-flatpak-")?;
+
+        // Strips the trailing instance number.
+        let (app_id, _) = app.rsplit_once('-')?;
+        Some(app_id)
+    }
+}
+
+/// Reads the cgroup v2 path of a process.
+fn read_cgroup(pid: u32) -> Option<String> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+
+    // The unified hierarchy is the one with the `0::` prefix.
+    cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+}
+
+/// Gets the systemd unit out of a cgroup path, being its last `.scope` or `.service` part.
+fn unit_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup
+        .rsplit('/')
+        .find(|part| part.ends_with(".scope") || part.ends_with(".service"))
+        .map(str::to_string)
+}
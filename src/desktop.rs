@@ -0,0 +1,105 @@
+use std::{env, path::PathBuf};
+
+/// The icon of a player, from the `Icon` key of its desktop file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DesktopIcon {
+    /// An icon name to look up in the icon theme (e.g., `spotify-client`).
+    Name(String),
+
+    /// An absolute path to the icon file.
+    Path(PathBuf),
+}
+
+/// Resolves a desktop entry (e.g., `spotify`) to the icon of its desktop file.
+///
+/// Looks for `<entry>.desktop` in the `applications` directory of `$XDG_DATA_HOME`
+/// and then of every `$XDG_DATA_DIRS`, the first file found wins.
+/// Returns None if there is no such file, or it doesn't have an icon.
+pub async fn resolve_desktop_icon(desktop_entry: &str) -> Option<DesktopIcon> {
+    // Some players send the whole file name.
+    let desktop_entry = desktop_entry.trim_end_matches(".desktop");
+    if desktop_entry.is_empty() || desktop_entry.contains('/') {
+        return None;
+    }
+
+    for data_dir in data_dirs() {
+        let path = data_dir
+            .join("applications")
+            .join(format!("{desktop_entry}.desktop"));
+
+        if let Ok(contents) = tokio::fs::read_to_string(&path).await {
+            return parse_icon(&contents);
+        }
+    }
+
+    None
+}
+
+/// The XDG data directories, by priority.
+fn data_dirs() -> Vec<PathBuf> {
+    let mut data_dirs = Vec::new();
+
+    match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(data_home) => data_dirs.push(PathBuf::from(data_home)),
+        None => {
+            if let Some(home) = env::var_os("HOME") {
+                data_dirs.push(PathBuf::from(home).join(".local/share"));
+            }
+        }
+    }
+
+    match env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+    {
+        Some(dirs) => data_dirs.extend(
+            dirs.split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        ),
+        None => data_dirs.extend([
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ]),
+    }
+
+    data_dirs
+}
+
+/// Gets the `Icon` key of the `[Desktop Entry]` group.
+fn parse_icon(contents: &str) -> Option<DesktopIcon> {
+    let mut in_entry = false;
+
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_entry = line == "[Desktop Entry]";
+            continue;
+        }
+
+        if !in_entry {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+
+        // Localized keys look like `Icon[de]`, only the plain one is wanted.
+        if key.trim() != "Icon" {
+            continue;
+        }
+
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        return if value.starts_with('/') {
+            Some(DesktopIcon::Path(PathBuf::from(value)))
+        } else {
+            Some(DesktopIcon::Name(value.to_string()))
+        };
+    }
+
+    None
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+use zvariant::OwnedValue;
+
+use crate::PlayerMetadata;
+
+/// What changed about a player, as carried by the properties changed events.
+///
+/// Players are allowed to invalidate a property instead of sending its new value,
+/// those get refetched before the event is sent out so they're never stale.
+///
+/// The raw `PropertiesChanged` body is kept along, so only the getters of what
+/// actually changed need to be called.
+#[derive(Debug, Clone, Default)]
+pub struct PropertiesDiff {
+    /// The interface the properties belong to.
+    interface: String,
+
+    /// The changed properties, with the values sent along.
+    changed: HashMap<String, OwnedValue>,
+
+    /// The properties that changed, but whose values weren't sent.
+    invalidated: Vec<String>,
+
+    /// The values of the invalidated properties, refetched from the player.
+    refetched: HashMap<String, OwnedValue>,
+}
+
+impl PropertiesDiff {
+    pub(crate) fn new(
+        interface: &str,
+        changed: HashMap<String, OwnedValue>,
+        invalidated: Vec<String>,
+    ) -> Self {
+        Self {
+            interface: interface.to_string(),
+            changed,
+            invalidated,
+            refetched: HashMap::new(),
+        }
+    }
+
+    /// Gets the interface the properties belong to (e.g. `org.mpris.MediaPlayer2.Player`).
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// Gets the changed properties, with the values the player sent along.
+    pub fn changed(&self) -> &HashMap<String, OwnedValue> {
+        &self.changed
+    }
+
+    /// Gets the names of the properties that changed, but whose values weren't sent.
+    pub fn invalidated(&self) -> &[String] {
+        &self.invalidated
+    }
+
+    /// Returns `true` if the property is either one of the changed or invalidated ones.
+    pub fn contains(&self, property: &str) -> bool {
+        self.changed.contains_key(property) || self.invalidated.iter().any(|name| name == property)
+    }
+
+    /// Gets the refetched value of an invalidated property.
+    pub fn get(&self, property: &str) -> Option<&OwnedValue> {
+        self.refetched.get(property)
+    }
+
+    /// Gets the values of every refetched property.
+    pub fn refetched(&self) -> &HashMap<String, OwnedValue> {
+        &self.refetched
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.invalidated.is_empty() && self.refetched.is_empty()
+    }
+
+    /// Hashes the properties and their values, in a stable order.
+    pub(crate) fn hash_values(&self, state: &mut impl Hasher) {
+        self.interface.hash(state);
+
+        let mut values: Vec<_> = self.changed.iter().chain(&self.refetched).collect();
+        values.sort_unstable_by_key(|(property, _)| property.as_str());
+
+        for (property, value) in values {
+            property.hash(state);
+            value.hash(state);
+        }
+
+        let mut invalidated: Vec<_> = self.invalidated.iter().collect();
+        invalidated.sort_unstable();
+        invalidated.hash(state);
+    }
+
+    pub(crate) fn insert(&mut self, property: &str, value: OwnedValue) {
+        self.refetched.insert(property.to_string(), value);
+    }
+
+    /// Merges the later changes in, like when debouncing.
+    ///
+    /// A property ends up either changed or invalidated, going by its latest change.
+    pub(crate) fn merge(&mut self, later: PropertiesDiff) {
+        for property in later.changed.keys() {
+            self.invalidated.retain(|name| name != property);
+            self.refetched.remove(property);
+        }
+
+        for property in &later.invalidated {
+            self.changed.remove(property);
+            if !self.invalidated.contains(property) {
+                self.invalidated.push(property.clone());
+            }
+        }
+
+        self.changed.extend(later.changed);
+        self.refetched.extend(later.refetched);
+    }
+}
+
+/// How the metadata of a player changed, as carried by the metadata changed events.
+#[derive(Debug, Clone)]
+pub struct MetadataDiff {
+    /// The metadata before the change, None if it wasn't known yet.
+    old: Option<PlayerMetadata<'static>>,
+
+    /// The metadata after the change.
+    new: PlayerMetadata<'static>,
+
+    /// The fields that were added, removed or changed, sorted.
+    changed_fields: Vec<String>,
+}
+
+impl MetadataDiff {
+    pub(crate) fn new(old: Option<PlayerMetadata<'static>>, new: PlayerMetadata<'static>) -> Self {
+        let mut changed_fields: Vec<String> = match &old {
+            Some(old) => old
+                .fields()
+                .filter(|field| new.get(field).is_none())
+                .chain(
+                    new.fields()
+                        .filter(|field| old.get(field) != new.get(field)),
+                )
+                .map(String::from)
+                .collect(),
+            None => new.fields().map(String::from).collect(),
+        };
+
+        changed_fields.sort();
+
+        Self {
+            old,
+            new,
+            changed_fields,
+        }
+    }
+
+    /// Gets the metadata before the change, None if it wasn't known yet.
+    pub fn old_metadata(&self) -> Option<&PlayerMetadata<'static>> {
+        self.old.as_ref()
+    }
+
+    /// Gets the metadata after the change.
+    pub fn new_metadata(&self) -> &PlayerMetadata<'static> {
+        &self.new
+    }
+
+    /// Gets the fields that were added, removed or changed (e.g., `xesam:title`).
+    pub fn changed_fields(&self) -> &[String] {
+        &self.changed_fields
+    }
+
+    /// Returns `true` if the field was added, removed or changed.
+    pub fn changed(&self, field: &str) -> bool {
+        self.changed_fields.iter().any(|changed| changed == field)
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changed_fields.is_empty()
+    }
+}
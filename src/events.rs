@@ -0,0 +1,185 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use futures::Stream;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    EventMask, MprisError, MprisEvent, MprisOptions, MprisPlayer, MprisResult, WatchHandle,
+};
+
+/// An event along with when it happened and its sequence number, as returned by
+/// [`crate::Mpris::recv_envelope`].
+#[derive(Clone)]
+pub struct EventEnvelope {
+    /// The event itself.
+    event: MprisEvent,
+
+    /// When the change behind the event got noticed.
+    received_at: SystemTime,
+
+    /// The place of the event among the events of its player.
+    sequence: u64,
+}
+
+impl EventEnvelope {
+    pub(crate) fn new(event: MprisEvent, received_at: SystemTime) -> Self {
+        Self {
+            event,
+            received_at,
+            sequence: 0,
+        }
+    }
+
+    pub(crate) fn set_sequence(&mut self, sequence: u64) {
+        self.sequence = sequence;
+    }
+
+    /// Gets the event.
+    pub fn event(&self) -> &MprisEvent {
+        &self.event
+    }
+
+    /// Takes the event out of the envelope.
+    pub fn into_event(self) -> MprisEvent {
+        self.event
+    }
+
+    /// Gets when the change behind the event got noticed.
+    ///
+    /// That's when the signal came in, or the poll noticed it, rather than when
+    /// the event got received. The held back properties changes (see
+    /// [`crate::MprisOptions::with_debounce`]) are stamped with their first change.
+    pub fn received_at(&self) -> SystemTime {
+        self.received_at
+    }
+
+    /// Gets the place of the event among the events of its player, counting up from zero.
+    ///
+    /// The numbers follow the order the events were queued in, and keep going up
+    /// for a player that comes back. An event dropped by the overflow policy (see
+    /// [`crate::MprisOptions::with_event_capacity`]) leaves a gap, so the consumers
+    /// passing the events on can tell when some got lost or reordered on the way.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+}
+
+/// A copy of every event of a [`crate::Mpris`], as returned by [`crate::Mpris::subscribe`].
+#[derive(Debug)]
+pub struct EventSubscription {
+    /// Event receiver.
+    receiver: broadcast::Receiver<MprisResult<MprisEvent>>,
+}
+
+impl EventSubscription {
+    pub(crate) fn new(receiver: broadcast::Receiver<MprisResult<MprisEvent>>) -> Self {
+        Self { receiver }
+    }
+
+    /// Receives the next event.
+    ///
+    /// Errors with [`MprisError::EventsLagged`] once after falling behind, with how many
+    /// events got skipped, the next call picks up from the oldest event still kept.
+    pub async fn recv(&mut self) -> MprisResult<MprisResult<MprisEvent>> {
+        match self.receiver.recv().await {
+            Ok(event) => Ok(event),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                Err(MprisError::EventsLagged(skipped))
+            }
+            Err(broadcast::error::RecvError::Closed) => Err(MprisError::FailedToRecvEvent),
+        }
+    }
+}
+
+/// The events of a single player, as returned by [`MprisPlayer::events`].
+///
+/// Runs its own watch task, independent of [`crate::Mpris::watch`] and of the other
+/// players, which stops once this gets dropped. The stream ends when the player goes
+/// away, right after the error telling so.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use mprizzle::Mpris;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mpris = Mpris::new().await?;
+///
+///     for player in mpris.list_players().await? {
+///         tokio::spawn(async move {
+///             let mut events = player.events();
+///
+///             while let Some(event) = events.next().await {
+///                 println!("{}: {:?}", player.identity().short(), event.map(|event| event.kind()));
+///             }
+///         });
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PlayerEvents {
+    /// Event receiver.
+    receiver: mpsc::UnboundedReceiver<MprisResult<MprisEvent>>,
+
+    /// Stops the watch task right away when this gets dropped.
+    _watch: WatchHandle,
+
+    /// The options, shared with the watch task.
+    options: watch::Sender<MprisOptions>,
+}
+
+impl PlayerEvents {
+    /// Starts watching the player with these options.
+    pub(crate) fn new(player: &MprisPlayer, options: MprisOptions) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let options = watch::channel(options).0;
+
+        let watch = player.watch(
+            sender,
+            CancellationToken::new(),
+            options.subscribe(),
+            EventMask::ALL,
+        );
+
+        Self {
+            receiver,
+            _watch: watch,
+            options,
+        }
+    }
+
+    /// Receives the next event of the player.
+    pub async fn recv(&mut self) -> MprisResult<MprisResult<MprisEvent>> {
+        self.receiver
+            .recv()
+            .await
+            .ok_or(MprisError::FailedToRecvEvent)
+    }
+
+    /// Gets the current options.
+    pub fn options(&self) -> MprisOptions {
+        self.options.borrow().clone()
+    }
+
+    /// Replaces the options, picked up by the already running watch task.
+    pub fn set_options(&self, options: MprisOptions) {
+        self.options.send_replace(options);
+    }
+}
+
+impl Stream for PlayerEvents {
+    type Item = MprisResult<MprisEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
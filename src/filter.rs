@@ -0,0 +1,139 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{EventKind, MprisEvent};
+
+/// Decides which events get sent out by [`crate::Mpris::watch_filtered`].
+///
+/// Filtering happens inside the watch tasks, before the events hit the channel.
+/// The property filter is checked against the property names carried by each
+/// `PropertiesChanged` signal, so a player re-emitting e.g. its `Volume` won't
+/// wake up a consumer that only cares about the `Metadata`.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::{EventFilter, EventKind, Mpris};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::new().await?;
+///
+///     // Only the attach / detach events and the track changes.
+///     let filter = EventFilter::all()
+///         .kinds([EventKind::PlayerAttached, EventKind::PlayerDetached])
+///         .properties(["Metadata"]);
+///
+///     mpris.watch_filtered(filter);
+///
+///     while let Ok(event) = mpris.recv().await? {
+///         println!("{:?}", event.kind());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    /// The allowed event kinds, `None` allowing all of them.
+    kinds: Option<HashSet<EventKind>>,
+
+    /// The allowed changed properties, `None` allowing all of them.
+    properties: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    /// Creates a filter that lets every event through.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Only lets through the events of the given kinds.
+    ///
+    /// Can be combined with [`EventFilter::properties`], which adds the
+    /// [`EventKind::PlayerPropertiesChanged`] and [`EventKind::PlayerRootPropertiesChanged`] kinds on its own.
+    pub fn kinds(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.kinds.get_or_insert_with(HashSet::new).extend(kinds);
+        self
+    }
+
+    /// Only lets through the properties changed events that changed
+    /// at least one of the given properties (e.g. `PlaybackStatus` or `Identity`).
+    pub fn properties<P>(mut self, properties: impl IntoIterator<Item = P>) -> Self
+    where
+        P: Into<String>,
+    {
+        self.properties
+            .get_or_insert_with(HashSet::new)
+            .extend(properties.into_iter().map(Into::into));
+
+        self
+    }
+
+    /// Returns `true` if the events of this kind are let through.
+    pub fn matches_kind(&self, kind: EventKind) -> bool {
+        match (&self.kinds, kind) {
+            (None, _) => true,
+            // Narrowing down the properties implies wanting the properties changed events.
+            (
+                Some(_),
+                EventKind::PlayerPropertiesChanged | EventKind::PlayerRootPropertiesChanged,
+            ) if self.properties.is_some() => true,
+            (Some(kinds), kind) => kinds.contains(&kind),
+        }
+    }
+
+    /// Returns `true` if a properties change touching these properties is let through.
+    ///
+    /// This only checks the properties, the kind of the change is checked with [`EventFilter::matches_kind`].
+    pub fn matches_properties<'a>(&self, mut properties: impl Iterator<Item = &'a str>) -> bool {
+        match &self.properties {
+            Some(allowed) => properties.any(|property| allowed.contains(property)),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if the event is let through.
+    ///
+    /// Properties changed events are only checked by kind here, as the
+    /// changed properties are checked by the watch tasks.
+    pub fn matches(&self, event: &MprisEvent) -> bool {
+        self.matches_kind(event.kind())
+    }
+}
+
+/// The closure of [`crate::Mpris::set_event_filter`].
+type EventPredicate = Box<dyn Fn(&MprisEvent) -> bool + Send>;
+
+/// The event filter closure, shared with the watch tasks so it can be swapped while they run.
+#[derive(Clone, Default)]
+pub(crate) struct SharedEventFilter(Arc<Mutex<Option<EventPredicate>>>);
+
+impl SharedEventFilter {
+    /// Replaces the closure, `None` letting every event through again.
+    pub(crate) fn set(&self, predicate: Option<EventPredicate>) {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) = predicate;
+    }
+
+    /// Returns `true` if the event is let through.
+    pub(crate) fn matches(&self, event: &MprisEvent) -> bool {
+        match &*self.0.lock().unwrap_or_else(|err| err.into_inner()) {
+            Some(predicate) => predicate(event),
+            None => true,
+        }
+    }
+}
+
+impl fmt::Debug for SharedEventFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let set = self
+            .0
+            .lock()
+            .map(|predicate| predicate.is_some())
+            .unwrap_or(true);
+        f.debug_tuple("SharedEventFilter").field(&set).finish()
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    any::Any,
+    mem,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex, Weak},
+    time::Duration,
+};
+
+use futures::FutureExt;
+use tokio::{
+    sync::watch,
+    task::{JoinError, JoinSet},
+    time::{self, Instant},
+};
+
+use crate::{MprisError, MprisResult, stats::EventSender};
+
+/// A running watch task, as returned by [`crate::Mpris::watch`] and [`crate::MprisPlayer::watch`].
+///
+/// The clones all refer to the same task, which stops once the last of them gets dropped,
+/// or once the event receiver goes away. [`crate::Mpris`] keeps the handle of its own watch,
+/// so that one runs for as long as the [`crate::Mpris`] is around.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::Mpris;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mpris = Mpris::new().await?;
+///     let handle = mpris.watch();
+///
+///     tokio::select! {
+///         result = handle.join() => result?,
+///         _ = tokio::signal::ctrl_c() => handle.abort(),
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WatchHandle(Arc<WatchState>);
+
+#[derive(Debug)]
+struct WatchState {
+    /// Set to stop the task.
+    abort: watch::Sender<bool>,
+
+    /// How the task stopped, None while it runs.
+    outcome: watch::Sender<Option<MprisResult<()>>>,
+
+    /// The player tasks spawned by the task, stopped along with it.
+    children: Mutex<Vec<WatchHandle>>,
+}
+
+impl Drop for WatchState {
+    /// Stops the task right away, rather than once it notices the receiver went away.
+    fn drop(&mut self) {
+        self.abort.send_replace(true);
+    }
+}
+
+/// A [`WatchHandle`] that doesn't keep its task running, handed to the task itself.
+#[derive(Debug, Clone)]
+pub(crate) struct WeakWatchHandle(Weak<WatchState>);
+
+impl WeakWatchHandle {
+    /// Stops the child along with the task, right away if the task is stopping already.
+    pub(crate) fn adopt(&self, child: WatchHandle) {
+        if let Some(state) = self.0.upgrade() {
+            WatchHandle(state).adopt(child);
+        }
+    }
+}
+
+impl WatchHandle {
+    /// Spawns the task, sending out the error it stops on before stopping.
+    pub(crate) fn spawn(
+        event_sender: EventSender,
+        task: impl Future<Output = MprisResult<()>> + Send + 'static,
+    ) -> Self {
+        let (handle, task) = Self::new(event_sender, |_| task);
+        tokio::spawn(task);
+
+        handle
+    }
+
+    /// Spawns the task among the tasks of a [`crate::Mpris`],
+    /// handing it its own handle to adopt the tasks it spawns.
+    pub(crate) fn spawn_in<F>(
+        tasks: &WatchTasks,
+        event_sender: EventSender,
+        task: impl FnOnce(WeakWatchHandle) -> F,
+    ) -> Self
+    where
+        F: Future<Output = MprisResult<()>> + Send + 'static,
+    {
+        let (handle, task) = Self::new(event_sender, task);
+        tasks.spawn(task);
+
+        handle
+    }
+
+    /// Wraps the task, to be spawned, so it can be followed and stopped through the handle.
+    ///
+    /// A panic stops the task with [`MprisError::TaskPanicked`], like any other error.
+    fn new<F>(
+        event_sender: EventSender,
+        task: impl FnOnce(WeakWatchHandle) -> F,
+    ) -> (Self, impl Future<Output = MprisResult<()>> + Send + 'static)
+    where
+        F: Future<Output = MprisResult<()>> + Send + 'static,
+    {
+        let state = Arc::new(WatchState {
+            abort: watch::channel(false).0,
+            outcome: watch::channel(None).0,
+            children: Mutex::new(Vec::new()),
+        });
+
+        let task_state = Arc::downgrade(&state);
+        let task = task(WeakWatchHandle(Weak::clone(&task_state)));
+
+        let mut aborted = state.abort.subscribe();
+
+        let task = async move {
+            let result = tokio::select! {
+                result = AssertUnwindSafe(task).catch_unwind() => {
+                    result.unwrap_or_else(|panic| Err(MprisError::TaskPanicked(panic_message(&*panic))))
+                }
+                _ = aborted.wait_for(|aborted| *aborted) => Ok(()),
+            };
+
+            // The receiver might be gone already, which is what stopped the task then.
+            if let Err(err) = &result {
+                let _ = event_sender.send(Err(err.clone())).await;
+            }
+
+            // Nobody is left to ask how it went when the handles are gone.
+            if let Some(state) = task_state.upgrade() {
+                state.outcome.send_replace(Some(result.clone()));
+            }
+
+            result
+        };
+
+        (Self(state), task)
+    }
+
+    /// Stops the child along with this task, for as long as it runs.
+    ///
+    /// The children that stopped on an error are kept, for [`WatchHandle::errors`].
+    fn adopt(&self, child: Self) {
+        let mut children = self.children();
+        children.retain(|child| !child.is_finished() || child.error().is_some());
+        children.push(child.clone());
+        drop(children);
+
+        // Checked after adding it, so an abort in between isn't missed.
+        if *self.0.abort.borrow() {
+            child.abort();
+        }
+    }
+
+    /// Waits for the task to stop, along with the player tasks it spawned.
+    ///
+    /// Errors with the error the task stopped on, the same one sent out as an event.
+    /// A task stopped by the receiver going away, or by [`WatchHandle::abort`], is Ok.
+    pub async fn join(&self) -> MprisResult<()> {
+        let mut outcome = self.0.outcome.subscribe();
+
+        // The state holds onto the sender, so the wait can't fail.
+        let result = match outcome.wait_for(Option::is_some).await {
+            Ok(outcome) => outcome.clone().unwrap_or(Ok(())),
+            Err(_) => Ok(()),
+        };
+
+        // The errors of the player tasks went out as events already.
+        let children = self.children().clone();
+        for child in children {
+            let _ = Box::pin(child.join()).await;
+        }
+
+        result
+    }
+
+    /// Stops the task, along with the player tasks it spawned.
+    ///
+    /// The events already sent out can still be received.
+    pub fn abort(&self) {
+        self.0.abort.send_replace(true);
+
+        for child in self.children().iter() {
+            child.abort();
+        }
+    }
+
+    /// Returns `true` if the task stopped, along with the player tasks it spawned.
+    pub fn is_finished(&self) -> bool {
+        self.0.outcome.borrow().is_some() && self.children().iter().all(Self::is_finished)
+    }
+
+    /// Gets the error the task stopped on, None while it runs or if it stopped without one.
+    pub fn error(&self) -> Option<MprisError> {
+        self.0.outcome.borrow().clone()?.err()
+    }
+
+    /// Gets the errors the task and the player tasks it spawned stopped on.
+    pub fn errors(&self) -> Vec<MprisError> {
+        let children = self.children().clone();
+
+        self.error()
+            .into_iter()
+            .chain(children.iter().flat_map(Self::errors))
+            .collect()
+    }
+
+    /// Returns `true` if both handles are of the same task.
+    #[cfg(test)]
+    pub(crate) fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    fn children(&self) -> std::sync::MutexGuard<'_, Vec<WatchHandle>> {
+        self.0
+            .children
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Every task spawned by a [`crate::Mpris`], kept to tell how they stopped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WatchTasks(Arc<Mutex<TasksState>>);
+
+#[derive(Debug, Default)]
+struct TasksState {
+    /// Aborts the tasks left when dropped.
+    set: JoinSet<MprisResult<()>>,
+
+    /// The errors of the tasks that stopped, until taken.
+    errors: Vec<MprisError>,
+}
+
+impl TasksState {
+    /// Collects the tasks that stopped, so the set doesn't keep growing.
+    fn reap(&mut self) {
+        while let Some(result) = self.set.try_join_next() {
+            self.collect(result);
+        }
+    }
+
+    /// Keeps the error the task stopped on, if any.
+    fn collect(&mut self, result: Result<MprisResult<()>, JoinError>) {
+        match result {
+            Ok(Err(err)) => self.errors.push(err),
+            Err(err) if err.is_panic() => self
+                .errors
+                .push(MprisError::TaskPanicked(panic_message(&*err.into_panic()))),
+            _ => {}
+        }
+    }
+}
+
+impl WatchTasks {
+    fn spawn(&self, task: impl Future<Output = MprisResult<()>> + Send + 'static) {
+        let mut state = self.lock();
+        state.reap();
+        state.set.spawn(task);
+    }
+
+    /// Takes the errors of the tasks that stopped since the last call.
+    pub(crate) fn errors(&self) -> Vec<MprisError> {
+        let mut state = self.lock();
+        state.reap();
+
+        mem::take(&mut state.errors)
+    }
+
+    /// Waits for every task to stop, then takes their errors, along with
+    /// [`MprisError::ShutdownTimedOut`] if some didn't stop in time.
+    ///
+    /// The tasks left get aborted.
+    pub(crate) async fn join(&self, timeout: Duration) -> Vec<MprisError> {
+        // Taken out, as the lock can't be held across the waits.
+        let mut state = mem::take(&mut *self.lock());
+        let deadline = Instant::now() + timeout;
+
+        while let Ok(Some(result)) = time::timeout_at(deadline, state.set.join_next()).await {
+            state.collect(result);
+        }
+
+        if !state.set.is_empty() {
+            state.errors.push(MprisError::ShutdownTimedOut(timeout));
+        }
+
+        state.errors
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TasksState> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Gets the message a panic got raised with.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".into())
+}
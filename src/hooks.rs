@@ -0,0 +1,44 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::MprisEvent;
+
+/// A closure of [`crate::Mpris::add_hook`].
+type EventHook = Box<dyn Fn(&MprisEvent) + Send + Sync>;
+
+/// The hooks, shared with the watch tasks so they can be added while they run.
+#[derive(Clone, Default)]
+pub(crate) struct SharedEventHooks(Arc<Mutex<Vec<EventHook>>>);
+
+impl SharedEventHooks {
+    /// Adds a hook, run after the ones already there.
+    pub(crate) fn add(&self, hook: EventHook) {
+        self.lock().push(hook);
+    }
+
+    /// Removes every hook.
+    pub(crate) fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Runs every hook on the event, in the order they got added.
+    pub(crate) fn run(&self, event: &MprisEvent) {
+        for hook in self.lock().iter() {
+            hook(event);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<EventHook>> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for SharedEventHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedEventHooks")
+            .field(&self.lock().len())
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{MprisError, MprisResult, proxies::DBUS_MPRIS_INTERFACE_NAME};
+use crate::{BusKind, MprisError, MprisResult, proxies::DBUS_MPRIS_INTERFACE_NAME};
 
 /// A struct representing the identity of [`crate::player::MprisPlayer`].
 ///
@@ -7,11 +7,13 @@ use crate::{MprisError, MprisResult, proxies::DBUS_MPRIS_INTERFACE_NAME};
 ///
 /// # Example
 ///
-/// ```no_run
-/// let spotify_identity = PlayerIdentity::new("org.mpris.MediaPlayer2.spotify".into())?;
+/// ```
+/// use mprizzle::PlayerIdentity;
+///
+/// let spotify_identity = PlayerIdentity::new("org.mpris.MediaPlayer2.spotify".into()).unwrap();
 ///
-/// assert!("spotify", spotify_identity.short());
-/// assert!("org.mpris.MediaPlayer2.spotify", spotify_identity.bus());
+/// assert_eq!("spotify", spotify_identity.short());
+/// assert_eq!("org.mpris.MediaPlayer2.spotify", spotify_identity.bus());
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PlayerIdentity {
@@ -20,6 +22,9 @@ pub struct PlayerIdentity {
 
     /// The full long bus name of the player.
     bus: String,
+
+    /// The extra bus the player is on, None for the main one.
+    source: Option<BusKind>,
 }
 
 impl PlayerIdentity {
@@ -43,7 +48,17 @@ impl PlayerIdentity {
             return Err(MprisError::InvalidBusName);
         }
 
-        Ok(Self { short, bus })
+        Ok(Self {
+            short,
+            bus,
+            source: None,
+        })
+    }
+
+    /// Tags the identity with the bus the player was found on.
+    pub(crate) fn with_source(mut self, source: Option<BusKind>) -> Self {
+        self.source = source;
+        self
     }
 
     /// Returns `true` if the short name matches the given string.
@@ -66,6 +81,58 @@ impl PlayerIdentity {
         self.matches_short(other) && self.matches_bus_prefix(other)
     }
 
+    /// Returns `true` if the given string is the short name, the name or the bus name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mprizzle::PlayerIdentity;
+    ///
+    /// let vlc_identity = PlayerIdentity::new("org.mpris.MediaPlayer2.vlc.instance1234".into()).unwrap();
+    ///
+    /// assert!(vlc_identity.matches_name("vlc"));
+    /// assert!(vlc_identity.matches_name("vlc.instance1234"));
+    /// assert!(vlc_identity.matches_name("org.mpris.MediaPlayer2.vlc.instance1234"));
+    /// assert!(!vlc_identity.matches_name("vlc.instance5678"));
+    /// ```
+    pub fn matches_name(&self, other: &str) -> bool {
+        self.matches_short(other) || self.name() == other || self.bus() == other
+    }
+
+    /// Returns `true` if a glob pattern, supporting `*` and `?` wildcards, matches
+    /// the short name, the name or the bus name.
+    ///
+    /// A pattern without wildcards matches like [`PlayerIdentity::matches_name`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use mprizzle::PlayerIdentity;
+    ///
+    /// let firefox_identity = PlayerIdentity::new("org.mpris.MediaPlayer2.firefox.instance_1_42".into()).unwrap();
+    ///
+    /// assert!(firefox_identity.matches_glob("firefox"));
+    /// assert!(firefox_identity.matches_glob("firefox.*"));
+    /// assert!(firefox_identity.matches_glob("org.mpris.MediaPlayer2.fire*"));
+    /// assert!(!firefox_identity.matches_glob("chromium.*"));
+    /// ```
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        [self.short(), self.name(), self.bus()]
+            .into_iter()
+            .any(|text| glob_match(pattern, text))
+    }
+
+    /// Returns `true` if the regex finds a match in the short name, the name or the bus name.
+    ///
+    /// Anchor the regex to match a whole name, `^firefox` matches the short name of every
+    /// instance while `firefox` would match a bus name containing it anywhere.
+    #[cfg(feature = "regex")]
+    pub fn matches_regex(&self, regex: &regex::Regex) -> bool {
+        [self.short(), self.name(), self.bus()]
+            .into_iter()
+            .any(|text| regex.is_match(text))
+    }
+
     /// Gets the short name.
     pub fn short(&self) -> &str {
         &self.short
@@ -75,4 +142,53 @@ impl PlayerIdentity {
     pub fn bus(&self) -> &str {
         &self.bus
     }
+
+    /// Gets the D-Bus bus the player is on, None for the main bus of the [`crate::Mpris`].
+    ///
+    /// Two players with the same bus name on different buses are told apart by it,
+    /// see [`crate::MprisBuilder::with_extra_bus`].
+    pub fn source(&self) -> Option<&BusKind> {
+        self.source.as_ref()
+    }
+
+    /// Gets the bus name without the mpris prefix, keeping the instance suffix
+    /// (e.g., `vlc.instance1234`) that the short name leaves out.
+    pub fn name(&self) -> &str {
+        &self.bus[DBUS_MPRIS_INTERFACE_NAME.len() + 1..]
+    }
+}
+
+/// Matches a text against a glob pattern supporting `*` and `?` wildcards.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+
+    // The position of the last `*` and the text position it was tried at.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry.
+                Some((star, star_t)) => {
+                    backtrack = Some((star, star_t + 1));
+                    p = star + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
 }
@@ -0,0 +1,106 @@
+use crate::{
+    compliance::{PLAYER_PROPERTIES, ROOT_PROPERTIES},
+    proxies::{
+        DBUS_MPRIS_INTERFACE_NAME, DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+        DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME, DBUS_MPRIS_TRACK_LIST_INTERFACE_NAME,
+    },
+};
+
+/// What a player implements of the MPRIS spec, see [`crate::MprisPlayer::interfaces`].
+///
+/// Read from the introspection data of the player, so it tells what the player
+/// claims to implement rather than what works.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerInterfaces {
+    /// The MPRIS interfaces the player declares.
+    interfaces: Vec<String>,
+
+    /// The optional properties of the root and player interfaces the player declares.
+    optional_properties: Vec<String>,
+}
+
+impl PlayerInterfaces {
+    /// Reads the interfaces and optional properties from the introspection data.
+    pub(crate) fn from_xml(xml: &str) -> Self {
+        let interfaces = [
+            DBUS_MPRIS_INTERFACE_NAME,
+            DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+            DBUS_MPRIS_TRACK_LIST_INTERFACE_NAME,
+            DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME,
+        ]
+        .into_iter()
+        .filter(|interface| interface_section(xml, interface).is_some())
+        .map(String::from)
+        .collect();
+
+        let optional_properties = [
+            (DBUS_MPRIS_INTERFACE_NAME, ROOT_PROPERTIES.as_slice()),
+            (
+                DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+                PLAYER_PROPERTIES.as_slice(),
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(interface, properties)| {
+            let section = interface_section(xml, interface).unwrap_or_default();
+
+            properties
+                .iter()
+                .filter(|(_, _, required)| !required)
+                .filter(move |(property, _, _)| {
+                    section.contains(&format!("<property name=\"{property}\""))
+                })
+                .map(|(property, _, _)| property.to_string())
+        })
+        .collect();
+
+        Self {
+            interfaces,
+            optional_properties,
+        }
+    }
+
+    /// Gets the MPRIS interfaces the player declares (e.g., `org.mpris.MediaPlayer2.TrackList`).
+    pub fn interfaces(&self) -> &[String] {
+        &self.interfaces
+    }
+
+    /// Gets the optional properties the player declares (e.g., `LoopStatus`, `Fullscreen`).
+    pub fn optional_properties(&self) -> &[String] {
+        &self.optional_properties
+    }
+
+    /// Returns `true` if the player declares the interface.
+    pub fn has_interface(&self, interface: &str) -> bool {
+        self.interfaces.iter().any(|declared| declared == interface)
+    }
+
+    /// Returns `true` if the player declares the optional property.
+    pub fn has_optional_property(&self, property: &str) -> bool {
+        self.optional_properties
+            .iter()
+            .any(|declared| declared == property)
+    }
+
+    /// Returns `true` if the player declares the player interface.
+    pub fn player(&self) -> bool {
+        self.has_interface(DBUS_MPRIS_PLAYER_INTERFACE_NAME)
+    }
+
+    /// Returns `true` if the player declares the track list interface.
+    pub fn track_list(&self) -> bool {
+        self.has_interface(DBUS_MPRIS_TRACK_LIST_INTERFACE_NAME)
+    }
+
+    /// Returns `true` if the player declares the playlists interface.
+    pub fn playlists(&self) -> bool {
+        self.has_interface(DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME)
+    }
+}
+
+/// Gets the part of the introspection data declaring the interface.
+pub(crate) fn interface_section<'x>(xml: &'x str, interface: &str) -> Option<&'x str> {
+    xml.split("<interface ")
+        .find(|section| section.starts_with(&format!("name=\"{interface}\"")))
+        .and_then(|section| section.split("</interface>").next())
+}
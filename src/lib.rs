@@ -10,8 +10,7 @@
 //!
 //! #[tokio::main]
 //! pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
-//!     let mut mpris = Mpris::new_without_options().await?;
-//!     let shared_players = mpris.players();
+//!     let mut mpris = Mpris::new().await?;
 //!
 //!     // Start watching for mpris events.
 //!     mpris.watch();
@@ -19,29 +18,21 @@
 //!     while let Ok(event) = mpris.recv().await? {
 //!         match event {
 //!             // Player Attached / Detached events.
-//!             MprisEvent::PlayerAttached(identity) => println!("NEW PLAYER = {}", identity.short()),
+//!             MprisEvent::PlayerAttached(player, _) => println!("NEW PLAYER = {}", player.identity().short()),
 //!             MprisEvent::PlayerDetached(identity) => println!("REMOVED PLAYER = {}", identity.short()),
 //!
 //!             // Player properties changed event.
-//!             MprisEvent::PlayerPropertiesChanged(identity) => {
-//!                 let players = shared_players.lock().await;
-//!                 if let Some(player) = players.iter().find(|p| *p.identity() == identity) {
-//!                     println!("PLAYER PROP CHANGED: {} = {:#?}", identity.short(), player.metadata().await?);
-//!                 }
-//!             },
+//!             MprisEvent::PlayerPropertiesChanged(identity, _) => println!("PLAYER PROP CHANGED: {}", identity.short()),
 //!
 //!             // Player seeked event.
-//!             MprisEvent::PlayerSeeked(identity) => {
-//!                 let players = shared_players.lock().await;
-//!                 if let Some(_) = players.iter().find(|p| *p.identity() == identity) {
-//!                     println!("PLAYER SEEKED: {}", identity.short());
-//!                 }
-//!             },
+//!             MprisEvent::PlayerSeeked(identity) => println!("PLAYER SEEKED: {}", identity.short()),
 //!
 //!             // Player position event.
 //!             MprisEvent::PlayerPosition(identity, position) => {
 //!                 println!("PLAYER POSITION: {} = {}", identity.short(), position.as_secs());
 //!             }
+//!
+//!             _ => {}
 //!         }
 //!     }
 //!
@@ -52,16 +43,104 @@
 mod mprizzle;
 pub use mprizzle::*;
 
+mod builder;
+pub use builder::*;
+
+mod capabilities;
+pub use capabilities::*;
+
+mod compliance;
+pub use compliance::*;
+
+mod controller;
+pub use controller::*;
+
+mod credentials;
+pub use credentials::*;
+
+mod desktop;
+pub use desktop::*;
+
+mod diff;
+pub use diff::*;
+
+mod events;
+pub use events::*;
+
+mod filter;
+pub use filter::*;
+
+mod handle;
+pub use handle::*;
+
 mod identity;
 pub use identity::*;
 
+mod interfaces;
+pub use interfaces::*;
+
+mod mask;
+pub use mask::*;
+
 mod metadata;
 pub use metadata::*;
 
+mod now_playing;
+pub use now_playing::*;
+
+mod options;
+pub use options::*;
+
 mod player;
 pub use player::*;
 
+mod playlist;
+pub use playlist::*;
+
+mod position;
+pub use position::*;
+
+mod quirks;
+pub use quirks::*;
+
+mod snapshot;
+pub use snapshot::*;
+
+mod stats;
+pub use stats::*;
+
+mod track;
+pub use track::*;
+
 mod status;
 pub use status::*;
 
+#[cfg(feature = "art")]
+mod art;
+#[cfg(feature = "art")]
+pub use art::*;
+
+#[cfg(feature = "monitor")]
+mod monitor;
+#[cfg(feature = "monitor")]
+pub use monitor::*;
+
+#[cfg(feature = "recording")]
+mod recording;
+#[cfg(feature = "recording")]
+pub use recording::*;
+
 mod proxies;
+pub use proxies::{MediaPlayer2Proxy, PlayerProxy, PlaylistsProxy, ProxyError, TrackListProxy};
+
+mod changes;
+
+mod hooks;
+
+mod lifecycle;
+
+mod queue;
+
+mod signals;
+
+mod single_playback;
@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+use crate::PlayerMetadata;
+
+/// The tracks shorter than this are never worth scrobbling.
+const SCROBBLE_MIN_LENGTH: Duration = Duration::from_secs(30);
+
+/// The play time after which any track is worth scrobbling, whatever its length.
+const SCROBBLE_MAX_PLAYED: Duration = Duration::from_secs(4 * 60);
+
+/// Follows the current track of a player from it starting to play until it finishes.
+///
+/// A track starts the first time it's seen playing, and finishes when its position
+/// gets close enough to its length, or when the player moves on to another track
+/// while it was still going. Pausing and resuming doesn't start a track again.
+///
+/// A started track becomes worth scrobbling after playing for half of its length
+/// or four minutes, whichever comes first, following the Last.fm rules.
+#[derive(Debug, Default)]
+pub(crate) struct TrackLifecycle {
+    /// The metadata of the started track, until it finishes.
+    started: Option<PlayerMetadata<'static>>,
+
+    /// Whether the current track already started, finished or not.
+    seen: bool,
+
+    /// How long the current track played, and since when it's been playing.
+    played: Duration,
+    playing_since: Option<Instant>,
+
+    /// Whether the current track was already found worth scrobbling.
+    scrobblable: bool,
+
+    /// Whether the current track was already found ending.
+    ending: bool,
+}
+
+impl TrackLifecycle {
+    /// Moves on to another track, returning the started one as finished.
+    pub(crate) fn track_changed(&mut self) -> Option<PlayerMetadata<'static>> {
+        self.seen = false;
+        self.played = Duration::ZERO;
+        self.playing_since = None;
+        self.scrobblable = false;
+        self.ending = false;
+        self.started.take()
+    }
+
+    /// Counts the time the started track has been playing for,
+    /// returning it the first time it becomes worth scrobbling.
+    pub(crate) fn tick_playing(&mut self) -> Option<PlayerMetadata<'static>> {
+        let now = Instant::now();
+        if let Some(since) = self.playing_since.replace(now) {
+            self.played += now - since;
+        }
+
+        let started = self.started.as_ref()?;
+        if self.scrobblable {
+            return None;
+        }
+
+        let threshold = match started.length().ok()? {
+            Some(length) if length < SCROBBLE_MIN_LENGTH => return None,
+            Some(length) => (length / 2).min(SCROBBLE_MAX_PLAYED),
+            None => SCROBBLE_MAX_PLAYED,
+        };
+
+        if self.played < threshold {
+            return None;
+        }
+
+        self.scrobblable = true;
+        Some(started.to_owned_metadata())
+    }
+
+    /// Stops counting the play time, until the track plays again.
+    pub(crate) fn paused(&mut self) {
+        self.playing_since = None;
+    }
+
+    /// Marks the current track as playing, returning it if this started it.
+    pub(crate) fn playing(
+        &mut self,
+        metadata: &PlayerMetadata<'static>,
+    ) -> Option<PlayerMetadata<'static>> {
+        if self.seen {
+            return None;
+        }
+
+        self.seen = true;
+        self.started = Some(metadata.to_owned_metadata());
+
+        Some(metadata.to_owned_metadata())
+    }
+
+    /// Returns the time left of the started track the first time it's within the lead of its end.
+    pub(crate) fn ending(&mut self, position: Duration, lead: Duration) -> Option<Duration> {
+        let length = self.started.as_ref()?.length().ok()??;
+        let remaining = length.saturating_sub(position);
+
+        if self.ending || remaining > lead {
+            return None;
+        }
+
+        self.ending = true;
+        Some(remaining)
+    }
+
+    /// Moves the position of the started track, returning it as finished if it's within the margin of its length.
+    ///
+    /// The tracks without a length only finish by changing tracks.
+    pub(crate) fn position(
+        &mut self,
+        position: Duration,
+        margin: Duration,
+    ) -> Option<PlayerMetadata<'static>> {
+        let length = self.started.as_ref()?.length().ok()??;
+
+        if position + margin < length {
+            return None;
+        }
+
+        self.started.take()
+    }
+}
@@ -1,27 +1,13 @@
-use mprizzle::Mpris;
+use clap::Parser;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let mut mpris = Mpris::new().await?;
-    mpris.watch();
+mod cli;
 
-    loop {
-        let event = mpris.recv().await?;
+#[tokio::main]
+async fn main() {
+    let cli = cli::Cli::parse();
 
-        match event {
-            Ok(event) => match event {
-                mprizzle::MprisEvent::PlayerAttached(id) => println!("attached"),
-                mprizzle::MprisEvent::PlayerDetached(id) => println!("detached"),
-                mprizzle::MprisEvent::PlayerPropertiesChanged(id) => println!("props changed"),
-                mprizzle::MprisEvent::PlayerSeeked(id) => println!("player seeked"),
-                mprizzle::MprisEvent::PlayerPosition(id, pos) => println!("pos changed"),
-            },
-            Err(err) => {
-                eprintln!("ERR: {err}");
-                break;
-            }
-        }
+    if let Err(err) = cli::run(cli).await {
+        eprintln!("mprizzle: {err}");
+        std::process::exit(1);
     }
-
-    Ok(())
 }
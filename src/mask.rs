@@ -0,0 +1,200 @@
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+use crate::EventKind;
+
+/// The event kinds an [`crate::Mpris`] can send out at all, fixed once it's created.
+///
+/// Unlike the [`crate::EventFilter`], which only drops the events, the mask decides what
+/// the watch tasks subscribe to: the signal streams and the poll ticker of the event kinds
+/// left out are never set up, so the players get no D-Bus traffic for them.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::{EventMask, Mpris};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::with_mask(EventMask::ATTACH | EventMask::METADATA | EventMask::POSITION).await?;
+///     mpris.watch();
+///
+///     while let Ok(event) = mpris.recv().await? {
+///         println!("{:?}", event.kind());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventMask(u32);
+
+impl EventMask {
+    /// No events at all.
+    pub const NONE: Self = Self(0);
+
+    /// [`EventKind::PlayerAttached`].
+    pub const ATTACH: Self = Self(1 << 0);
+
+    /// [`EventKind::PlayerDetached`].
+    pub const DETACH: Self = Self(1 << 1);
+
+    /// [`EventKind::PlayerPropertiesChanged`].
+    pub const PROPERTIES: Self = Self(1 << 2);
+
+    /// [`EventKind::PlayerMetadataChanged`].
+    pub const METADATA: Self = Self(1 << 3);
+
+    /// [`EventKind::PlayerTrackChanged`].
+    pub const TRACK: Self = Self(1 << 4);
+
+    /// [`EventKind::PlayerTrackStarted`].
+    pub const TRACK_STARTED: Self = Self(1 << 5);
+
+    /// [`EventKind::PlayerTrackFinished`].
+    pub const TRACK_FINISHED: Self = Self(1 << 6);
+
+    /// [`EventKind::PlayerTrackScrobblable`].
+    pub const TRACK_SCROBBLABLE: Self = Self(1 << 7);
+
+    /// [`EventKind::PlayerTrackEnding`].
+    pub const TRACK_ENDING: Self = Self(1 << 8);
+
+    /// [`EventKind::PlayerPlaybackStatusChanged`].
+    pub const STATUS: Self = Self(1 << 9);
+
+    /// [`EventKind::PlayerRootPropertiesChanged`].
+    pub const ROOT_PROPERTIES: Self = Self(1 << 10);
+
+    /// [`EventKind::PlayerPlaylistChanged`].
+    pub const PLAYLIST: Self = Self(1 << 11);
+
+    /// [`EventKind::PlayerSeeked`].
+    pub const SEEKED: Self = Self(1 << 12);
+
+    /// [`EventKind::PlayerPosition`].
+    pub const POSITION: Self = Self(1 << 13);
+
+    /// [`EventKind::PlayerCapabilitiesChanged`].
+    pub const CAPABILITIES: Self = Self(1 << 14);
+
+    /// [`EventKind::PlayerAutoPaused`].
+    pub const AUTO_PAUSED: Self = Self(1 << 15);
+
+    /// Every event.
+    pub const ALL: Self = Self((1 << 16) - 1);
+
+    /// The events following the current track, from it starting to play until it finishes.
+    pub const LIFECYCLE: Self = Self(
+        Self::TRACK_STARTED.0
+            | Self::TRACK_FINISHED.0
+            | Self::TRACK_SCROBBLABLE.0
+            | Self::TRACK_ENDING.0,
+    );
+
+    /// Returns `true` if every event of the other mask is in this one.
+    pub fn contains(&self, other: EventMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if any event of the other mask is in this one.
+    pub fn intersects(&self, other: EventMask) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Returns `true` if the events of this kind are in the mask.
+    pub fn contains_kind(&self, kind: EventKind) -> bool {
+        self.contains(kind.into())
+    }
+
+    /// Returns `true` if the player tasks have to listen to `PropertiesChanged`.
+    ///
+    /// The position follows the track changes and the playback status through it.
+    pub(crate) fn needs_properties(&self) -> bool {
+        self.intersects(
+            Self::PROPERTIES
+                | Self::ROOT_PROPERTIES
+                | Self::METADATA
+                | Self::TRACK
+                | Self::STATUS
+                | Self::CAPABILITIES
+                | Self::LIFECYCLE
+                | Self::POSITION,
+        )
+    }
+
+    /// Returns `true` if the player tasks have to listen to `Seeked`.
+    pub(crate) fn needs_seeked(&self) -> bool {
+        self.intersects(Self::SEEKED | Self::POSITION | Self::TRACK_ENDING | Self::TRACK_FINISHED)
+    }
+
+    /// Returns `true` if the player tasks have to listen to `PlaylistChanged`.
+    pub(crate) fn needs_playlists(&self) -> bool {
+        self.intersects(Self::PLAYLIST)
+    }
+
+    /// Returns `true` if the player tasks have to poll the players.
+    ///
+    /// Some players don't signal their playback status, so polling catches it.
+    pub(crate) fn needs_ticker(&self) -> bool {
+        self.intersects(Self::POSITION | Self::STATUS | Self::LIFECYCLE)
+    }
+}
+
+impl Default for EventMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl From<EventKind> for EventMask {
+    fn from(kind: EventKind) -> Self {
+        match kind {
+            EventKind::PlayerAttached => Self::ATTACH,
+            EventKind::PlayerDetached => Self::DETACH,
+            EventKind::PlayerPropertiesChanged => Self::PROPERTIES,
+            EventKind::PlayerMetadataChanged => Self::METADATA,
+            EventKind::PlayerTrackChanged => Self::TRACK,
+            EventKind::PlayerTrackStarted => Self::TRACK_STARTED,
+            EventKind::PlayerTrackFinished => Self::TRACK_FINISHED,
+            EventKind::PlayerTrackScrobblable => Self::TRACK_SCROBBLABLE,
+            EventKind::PlayerTrackEnding => Self::TRACK_ENDING,
+            EventKind::PlayerPlaybackStatusChanged => Self::STATUS,
+            EventKind::PlayerRootPropertiesChanged => Self::ROOT_PROPERTIES,
+            EventKind::PlayerPlaylistChanged => Self::PLAYLIST,
+            EventKind::PlayerSeeked => Self::SEEKED,
+            EventKind::PlayerPosition => Self::POSITION,
+            EventKind::PlayerCapabilitiesChanged => Self::CAPABILITIES,
+            EventKind::PlayerAutoPaused => Self::AUTO_PAUSED,
+        }
+    }
+}
+
+impl FromIterator<EventKind> for EventMask {
+    fn from_iter<I: IntoIterator<Item = EventKind>>(kinds: I) -> Self {
+        kinds
+            .into_iter()
+            .fold(Self::NONE, |mask, kind| mask | kind.into())
+    }
+}
+
+impl BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for EventMask {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for EventMask {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+}
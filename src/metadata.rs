@@ -1,9 +1,13 @@
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
 
 use crate::{MprisError, MprisResult};
 
 /// Represents errors that can occur in MPRIS Metadata operations.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum MetadataError {
     #[error("The field `{field}` expected: `{expected}` but got: {got}.")]
     MetadataInvalidFieldType {
@@ -17,6 +21,12 @@ pub enum MetadataError {
 #[derive(Debug, Clone)]
 pub struct TrackId(String);
 
+impl TrackId {
+    pub(crate) fn new(track_id: impl Into<String>) -> Self {
+        Self(track_id.into())
+    }
+}
+
 impl AsRef<str> for TrackId {
     fn as_ref(&self) -> &str {
         &self.0
@@ -27,7 +37,7 @@ impl AsRef<str> for TrackId {
 ///
 /// This struct stores key-value pairs of metadata properties retrieved from an MPRIS-compatible player.
 /// Metadata includes information such as track title, artist, album, playback details, etc.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PlayerMetadata<'a> {
     metadata: HashMap<String, zvariant::Value<'a>>,
 }
@@ -44,6 +54,11 @@ impl<'a> PlayerMetadata<'a> {
         Self { metadata }
     }
 
+    /// Gets the raw value of a metadata field (e.g., `xesam:url`).
+    pub fn get(&self, field: &str) -> Option<&zvariant::Value<'a>> {
+        self.metadata.get(field)
+    }
+
     /// Metadata mpris:trackid.
     ///
     /// Returns Err when mpris:trackid is somehow a different type.
@@ -172,4 +187,55 @@ impl<'a> PlayerMetadata<'a> {
             })
             .unwrap_or(Ok(None))
     }
+
+    /// Metadata xesam:asText, the lyrics of the track.
+    ///
+    /// Returns Err when xesam:asText is somehow a different type.
+    /// Returns None when xesam:asText doesn't exists.
+    pub fn lyrics(&self) -> MprisResult<Option<String>> {
+        self.metadata
+            .get("xesam:asText")
+            .map(|lyrics| match lyrics {
+                zvariant::Value::Str(lyrics) => Ok(Some(lyrics.to_string())),
+                _ => Err(MprisError::MetadataErr(
+                    MetadataError::MetadataInvalidFieldType {
+                        field: "xesam:asText".into(),
+                        expected: "s".into(),
+                        got: lyrics.value_signature().to_string(),
+                    },
+                )),
+            })
+            .unwrap_or(Ok(None))
+    }
+
+    /// Iterates over the names of the metadata fields.
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.metadata.keys().map(String::as_str)
+    }
+
+    /// Hashes the fields and their values, in a stable order.
+    pub(crate) fn hash_fields(&self, state: &mut impl Hasher) {
+        let mut fields: Vec<_> = self.metadata.iter().collect();
+        fields.sort_unstable_by_key(|(field, _)| field.as_str());
+
+        for (field, value) in fields {
+            field.hash(state);
+            value.hash(state);
+        }
+    }
+
+    /// Copies the metadata out of the message it's borrowed from.
+    ///
+    /// The values that can't be copied (file descriptors) are left out.
+    pub(crate) fn to_owned_metadata(&self) -> PlayerMetadata<'static> {
+        PlayerMetadata::new(
+            self.metadata
+                .iter()
+                .filter_map(|(field, value)| {
+                    let value = value.try_to_owned().ok()?;
+                    Some((field.clone(), value.into()))
+                })
+                .collect(),
+        )
+    }
 }
@@ -0,0 +1,259 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+use zbus::{
+    MatchRule, Message, MessageStream,
+    fdo::MonitoringProxy,
+    message::{Flags, Type},
+};
+
+use crate::{
+    BusKind, MprisError, MprisResult,
+    proxies::{DBUS_MPRIS_INTERFACE_NAME, DBUS_MPRIS_INTERFACE_PATH},
+};
+
+/// How many of the latest calls get their replies matched, the older ones are taken as never getting one.
+///
+/// A call can go without a reply for good, when its caller or the player goes away.
+const PENDING_CALLS_CAPACITY: usize = 1024;
+
+/// The kind of a monitored message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitoredKind {
+    MethodCall,
+    MethodReturn,
+    Error,
+    Signal,
+}
+
+impl fmt::Display for MonitoredKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitoredKind::MethodCall => write!(f, "call"),
+            MonitoredKind::MethodReturn => write!(f, "return"),
+            MonitoredKind::Error => write!(f, "error"),
+            MonitoredKind::Signal => write!(f, "signal"),
+        }
+    }
+}
+
+/// An mpris related message seen on the bus, see [`MprisMonitor`].
+#[derive(Debug, Clone)]
+pub struct MonitoredMessage {
+    message: Message,
+}
+
+impl MonitoredMessage {
+    /// Gets the kind of the message.
+    pub fn kind(&self) -> MonitoredKind {
+        match self.message.header().message_type() {
+            Type::MethodCall => MonitoredKind::MethodCall,
+            Type::MethodReturn => MonitoredKind::MethodReturn,
+            Type::Error => MonitoredKind::Error,
+            Type::Signal => MonitoredKind::Signal,
+        }
+    }
+
+    /// Gets the unique name of the sender (e.g., `:1.42`).
+    pub fn sender(&self) -> Option<String> {
+        self.message
+            .header()
+            .sender()
+            .map(|sender| sender.to_string())
+    }
+
+    /// Gets the name the message was sent to, None for the broadcast signals.
+    pub fn destination(&self) -> Option<String> {
+        self.message
+            .header()
+            .destination()
+            .map(|destination| destination.to_string())
+    }
+
+    /// Gets the interface of a call or a signal.
+    pub fn interface(&self) -> Option<String> {
+        self.message
+            .header()
+            .interface()
+            .map(|interface| interface.to_string())
+    }
+
+    /// Gets the method or signal name of a call or a signal, or the error name of an error.
+    pub fn member(&self) -> Option<String> {
+        let header = self.message.header();
+
+        header
+            .member()
+            .map(|member| member.to_string())
+            .or_else(|| header.error_name().map(|error| error.to_string()))
+    }
+
+    /// Gets the body of the message, formatted.
+    pub fn body(&self) -> String {
+        let body = self.message.body();
+
+        if body.signature().to_string().is_empty() {
+            return String::new();
+        }
+
+        match body.deserialize::<zvariant::Structure>() {
+            Ok(body) => body.to_string(),
+            Err(err) => format!("<{err}>"),
+        }
+    }
+
+    /// Gets the raw message.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+}
+
+/// A passive monitor of the mpris traffic on the session bus.
+///
+/// Sees every call made to the players, their replies, and the signals they send out,
+/// without anyone noticing. Meant for debugging how the players and other controllers talk.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::StreamExt;
+/// use mprizzle::MprisMonitor;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut monitor = MprisMonitor::new().await?;
+///
+///     while let Some(message) = monitor.next().await {
+///         let message = message?;
+///         println!("{} {:?} {:?}", message.kind(), message.interface(), message.member());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+pub struct MprisMonitor {
+    stream: MessageStream,
+
+    /// The calls waiting for a reply, by who made them and their serial.
+    pending_calls: HashSet<(String, u32)>,
+
+    /// The latest calls, oldest first, for forgetting the ones past the capacity.
+    latest_calls: VecDeque<(String, u32)>,
+}
+
+impl MprisMonitor {
+    /// Starts monitoring the session bus, on a connection of its own.
+    ///
+    /// The bus has to allow becoming a monitor, which the session buses do for their own user.
+    pub async fn new() -> MprisResult<Self> {
+        Self::new_on(BusKind::Session).await
+    }
+
+    /// Starts monitoring another bus, see [`crate::MprisBuilder::with_bus`].
+    ///
+    /// The system bus usually only lets root become a monitor.
+    pub async fn new_on(bus: BusKind) -> MprisResult<Self> {
+        let connection = bus.connect().await?;
+
+        let stream = MessageStream::from(&connection);
+
+        // Every mpris object lives on the same path, the replies can't be matched on though.
+        let rules = [
+            MatchRule::builder()
+                .path(DBUS_MPRIS_INTERFACE_PATH)
+                .map_err(|err| MprisError::Other(err.to_string()))?
+                .build(),
+            MatchRule::builder().msg_type(Type::MethodReturn).build(),
+            MatchRule::builder().msg_type(Type::Error).build(),
+        ];
+
+        let monitoring_proxy = MonitoringProxy::new(&connection)
+            .await
+            .map_err(|err| MprisError::FailedToCallFn("BecomeMonitor".into(), err.to_string()))?;
+
+        monitoring_proxy
+            .become_monitor(&rules, 0)
+            .await
+            .map_err(|err| MprisError::FailedToCallFn("BecomeMonitor".into(), err.to_string()))?;
+
+        Ok(Self {
+            stream,
+            pending_calls: HashSet::new(),
+            latest_calls: VecDeque::with_capacity(PENDING_CALLS_CAPACITY),
+        })
+    }
+
+    /// Returns `true` if the message is part of the mpris traffic, keeping track of the calls.
+    fn is_mpris(&mut self, message: &Message) -> bool {
+        let header = message.header();
+
+        match header.message_type() {
+            Type::MethodReturn | Type::Error => {
+                let (Some(destination), Some(reply_serial)) =
+                    (header.destination(), header.reply_serial())
+                else {
+                    return false;
+                };
+
+                self.pending_calls
+                    .remove(&(destination.to_string(), reply_serial.get()))
+            }
+            Type::MethodCall | Type::Signal => {
+                // The path is shared with a few non mpris interfaces (e.g., Introspectable).
+                let mpris = header.interface().is_some_and(|interface| {
+                    interface.starts_with(DBUS_MPRIS_INTERFACE_NAME)
+                        || interface.as_str() == "org.freedesktop.DBus.Properties"
+                });
+
+                let expects_reply = header.message_type() == Type::MethodCall
+                    && !header.primary().flags().contains(Flags::NoReplyExpected);
+
+                if mpris
+                    && expects_reply
+                    && let Some(sender) = header.sender()
+                {
+                    let call = (sender.to_string(), header.primary().serial_num().get());
+
+                    if self.latest_calls.len() == PENDING_CALLS_CAPACITY
+                        && let Some(oldest) = self.latest_calls.pop_front()
+                    {
+                        self.pending_calls.remove(&oldest);
+                    }
+
+                    self.latest_calls.push_back(call.clone());
+                    self.pending_calls.insert(call);
+                }
+
+                mpris
+            }
+        }
+    }
+}
+
+impl Stream for MprisMonitor {
+    type Item = MprisResult<MonitoredMessage>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let message = match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(message))) => message,
+                Poll::Ready(Some(Err(err))) => {
+                    return Poll::Ready(Some(Err(MprisError::Other(format!(
+                        "Failed to receive a monitored message: {err}"
+                    )))));
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            };
+
+            if self.is_mpris(&message) {
+                return Poll::Ready(Some(Ok(MonitoredMessage { message })));
+            }
+        }
+    }
+}
@@ -1,16 +1,55 @@
 use std::collections::HashMap;
-use std::{sync::Arc, time::Duration};
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
 
+use crate::builder::{BusKind, MprisBuilder, PlayerFilter, PlayerRanking};
+use crate::filter::{EventFilter, SharedEventFilter};
+use crate::handle::{WatchTasks, WeakWatchHandle};
+use crate::hooks::SharedEventHooks;
+use crate::now_playing::NowPlayingTracker;
 use crate::player::MprisPlayer;
-use crate::proxies::{self, DBUS_MPRIS_INTERFACE_NAME, ProxyError};
-use crate::{MetadataError, identity};
+use crate::proxies::{self, ProxyError};
+use crate::queue::{EventQueue, EventReceiver};
+use crate::signals::{SignalDispatcher, SignalSource};
+use crate::single_playback;
+use crate::stats::{EventSender, Stats};
+use crate::{
+    AllPlayers, Capabilities, EventEnvelope, EventMask, EventSubscription, MetadataDiff,
+    MetadataError, MprisOptions, NowPlaying, PlaybackStatus, PlayerMetadata, PlayerSnapshot,
+    PlayerStats, Playlist, PropertiesDiff, WatchHandle, identity,
+};
 use crate::{identity::PlayerIdentity, player::PlayerError};
-use futures::StreamExt;
-use tokio::sync::{Mutex, broadcast, mpsc};
+use futures::{StreamExt, future, stream};
+use tokio::sync::{Mutex, broadcast, mpsc, mpsc::error::TryRecvError, watch};
+use tokio_util::sync::CancellationToken;
 use zbus::Connection;
 
+/// How long to wait before the first attempt at reconnecting to D-Bus.
+const RECONNECT_BACKOFF_MIN: Duration = Duration::from_millis(500);
+
+/// How long to wait between the attempts at reconnecting to D-Bus, at most.
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// How long the watch tasks have to stop, see [`Mpris::shutdown`].
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How many of the players already on the bus get created at once when watching.
+const ATTACH_CONCURRENCY: usize = 8;
+
+/// Orders the players matching a name, putting a full name before a short one
+/// shared by several instances, then going by bus name.
+fn name_rank<'a>(identity: &'a PlayerIdentity, name: &str) -> (bool, &'a str) {
+    (
+        identity.short() == name && identity.name() != name,
+        identity.bus(),
+    )
+}
+
 /// Represents errors that can occur in MPRIS operations.
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum MprisError {
     #[error("Failed to connect to D-BUS: {0}")]
     FailedToConnectDbus(String),
@@ -21,6 +60,9 @@ pub enum MprisError {
     #[error("Failed to receive mpris event.")]
     FailedToRecvEvent,
 
+    #[error("The subscription fell behind, {0} events were skipped.")]
+    EventsLagged(u64),
+
     #[error("Failed to call D-Bus function: {0}.")]
     FailedToCallFn(String, String),
 
@@ -36,31 +78,252 @@ pub enum MprisError {
     #[error("{0}")]
     ProxyErr(#[from] ProxyError),
 
+    #[cfg(feature = "art")]
+    #[error("{0}")]
+    ArtErr(#[from] crate::ArtError),
+
+    #[cfg(feature = "recording")]
+    #[error("Failed to record the event: {0}.")]
+    FailedToRecord(String),
+
+    #[cfg(feature = "recording")]
+    #[error("Failed to replay the recording: {0}.")]
+    FailedToReplay(String),
+
+    #[error("Lost the connection to D-Bus.")]
+    ConnectionLost,
+
+    #[error("A watch task panicked: {0}.")]
+    TaskPanicked(String),
+
+    #[error("The watch tasks took longer than {0:?} to stop.")]
+    ShutdownTimedOut(Duration),
+
+    #[error("Failed to shut down cleanly, {} errors came up.", .0.len())]
+    FailedToShutdown(Vec<MprisError>),
+
+    #[error("No player matching {0} showed up within {1:?}.")]
+    PlayerWaitTimedOut(String, Duration),
+
+    #[error("There is no active player.")]
+    NoActivePlayer,
+
+    #[error("Every player went away.")]
+    AllPlayersGone,
+
     #[error("{0}")]
     Other(String),
 }
 
+impl MprisError {
+    /// Gets the bus name of the player the error came from, if it came from one.
+    pub fn bus(&self) -> Option<&str> {
+        match self {
+            MprisError::PlayerErr(err) => Some(err.bus()),
+            MprisError::ProxyErr(err) => Some(err.bus()),
+            _ => None,
+        }
+    }
+}
+
 /// A shorthand for `Result<T, MprisError>`.
 pub type MprisResult<T> = Result<T, MprisError>;
 
+/// The players attached while watching, see [`Mpris::players`].
+pub type SharedPlayers = Arc<Mutex<HashMap<PlayerIdentity, Arc<MprisPlayer>>>>;
+
 /// Represents events triggered by changes in an MPRIS media player.
+#[derive(Clone)]
 pub enum MprisEvent {
     /// Triggers when a new player has been attached or added.
     /// This is the only event that has the MprisPlayer on it.
     /// You should add it on state to be managed.
-    PlayerAttached(MprisPlayer),
+    ///
+    /// Comes with the state of the player when it attached, so it can be shown right away.
+    /// The snapshot is empty when the player couldn't be asked for it.
+    PlayerAttached(MprisPlayer, PlayerSnapshot),
 
     /// Triggers when an existing player has been detached or removed.
     PlayerDetached(PlayerIdentity),
 
     /// Triggers when one of the player's properties changed.
-    PlayerPropertiesChanged(PlayerIdentity),
+    PlayerPropertiesChanged(PlayerIdentity, PropertiesDiff),
+
+    /// Triggers when the player's metadata changed, with what changed about it.
+    ///
+    /// Unlike [`MprisEvent::PlayerPropertiesChanged`], this only fires when a metadata field actually changed.
+    PlayerMetadataChanged(PlayerIdentity, MetadataDiff),
+
+    /// Triggers when the player moved on to another track, with the metadata of the new one.
+    ///
+    /// The tracks are told apart with the [`crate::TrackIdentity`] of the options,
+    /// so metadata updates of the same track (e.g., the art loading in) don't trigger it.
+    PlayerTrackChanged(PlayerIdentity, PlayerMetadata<'static>),
+
+    /// Triggers when a track starts playing for the first time, with its metadata.
+    PlayerTrackStarted(PlayerIdentity, PlayerMetadata<'static>),
+
+    /// Triggers when a started track finished, with its metadata.
+    ///
+    /// A track finishes when its position gets within a poll interval of its length,
+    /// or when the player moves on to another track while it was going.
+    PlayerTrackFinished(PlayerIdentity, PlayerMetadata<'static>),
+
+    /// Triggers once a started track played long enough to be scrobbled, with its metadata.
+    ///
+    /// That's half of its length or four minutes of play time, whichever comes first,
+    /// the tracks shorter than 30 seconds never trigger it.
+    PlayerTrackScrobblable(PlayerIdentity, PlayerMetadata<'static>),
+
+    /// Triggers once a started track gets close to its end, with the time it has left.
+    ///
+    /// How close is set with [`crate::MprisOptions::with_track_ending`], handy for crossfading
+    /// or fetching what comes next ahead of time.
+    PlayerTrackEnding(PlayerIdentity, Duration),
+
+    /// Triggers when the player's playback status changed, with the old and the new one.
+    PlayerPlaybackStatusChanged(PlayerIdentity, PlaybackStatus, PlaybackStatus),
+
+    /// Triggers when one of the player's `Can*` properties changed, with all of them.
+    ///
+    /// Players flip them as they go, like `CanGoNext` at the end of the playlist,
+    /// so the controls can be turned on and off along.
+    PlayerCapabilitiesChanged(PlayerIdentity, Capabilities),
+
+    /// Triggers when one of the player's root properties changed (e.g., `Identity` or `Fullscreen`).
+    PlayerRootPropertiesChanged(PlayerIdentity, PropertiesDiff),
+
+    /// Triggers when one of the player's playlists changed, like when it got renamed.
+    PlayerPlaylistChanged(PlayerIdentity, Playlist),
 
     /// Triggers when one of the player's position changed due to the user manually changing it.
     PlayerSeeked(PlayerIdentity),
 
     /// Triggers when one of the player's position changed.
     PlayerPosition(PlayerIdentity, Duration),
+
+    /// Triggers when the player got paused as the other one started playing,
+    /// see [`crate::MprisOptions::with_single_playback`].
+    PlayerAutoPaused(PlayerIdentity, PlayerIdentity),
+}
+
+/// The kind of an [`MprisEvent`], without its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    PlayerAttached,
+    PlayerDetached,
+    PlayerPropertiesChanged,
+    PlayerMetadataChanged,
+    PlayerTrackChanged,
+    PlayerTrackStarted,
+    PlayerTrackFinished,
+    PlayerTrackScrobblable,
+    PlayerTrackEnding,
+    PlayerPlaybackStatusChanged,
+    PlayerCapabilitiesChanged,
+    PlayerRootPropertiesChanged,
+    PlayerPlaylistChanged,
+    PlayerSeeked,
+    PlayerPosition,
+    PlayerAutoPaused,
+}
+
+impl MprisEvent {
+    /// Gets the kind of this event.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            MprisEvent::PlayerAttached(_, _) => EventKind::PlayerAttached,
+            MprisEvent::PlayerDetached(_) => EventKind::PlayerDetached,
+            MprisEvent::PlayerPropertiesChanged(_, _) => EventKind::PlayerPropertiesChanged,
+            MprisEvent::PlayerMetadataChanged(_, _) => EventKind::PlayerMetadataChanged,
+            MprisEvent::PlayerTrackChanged(_, _) => EventKind::PlayerTrackChanged,
+            MprisEvent::PlayerTrackStarted(_, _) => EventKind::PlayerTrackStarted,
+            MprisEvent::PlayerTrackFinished(_, _) => EventKind::PlayerTrackFinished,
+            MprisEvent::PlayerTrackScrobblable(_, _) => EventKind::PlayerTrackScrobblable,
+            MprisEvent::PlayerTrackEnding(_, _) => EventKind::PlayerTrackEnding,
+            MprisEvent::PlayerPlaybackStatusChanged(_, _, _) => {
+                EventKind::PlayerPlaybackStatusChanged
+            }
+            MprisEvent::PlayerCapabilitiesChanged(_, _) => EventKind::PlayerCapabilitiesChanged,
+            MprisEvent::PlayerRootPropertiesChanged(_, _) => EventKind::PlayerRootPropertiesChanged,
+            MprisEvent::PlayerPlaylistChanged(_, _) => EventKind::PlayerPlaylistChanged,
+            MprisEvent::PlayerSeeked(_) => EventKind::PlayerSeeked,
+            MprisEvent::PlayerPosition(_, _) => EventKind::PlayerPosition,
+            MprisEvent::PlayerAutoPaused(_, _) => EventKind::PlayerAutoPaused,
+        }
+    }
+
+    /// Hashes the kind and the data of this event, to tell the duplicates apart.
+    ///
+    /// None for the events that are never duplicates, like the attach / detach events,
+    /// or the ones whose data doesn't tell what changed, like a seek or the empty
+    /// properties changes. The positions only count their whole seconds.
+    pub(crate) fn fingerprint(&self) -> Option<u64> {
+        let mut hasher = DefaultHasher::new();
+        self.kind().hash(&mut hasher);
+        self.identity().hash(&mut hasher);
+
+        match self {
+            MprisEvent::PlayerAttached(_, _)
+            | MprisEvent::PlayerDetached(_)
+            | MprisEvent::PlayerSeeked(_)
+            | MprisEvent::PlayerAutoPaused(_, _) => return None,
+            MprisEvent::PlayerPropertiesChanged(_, diff)
+            | MprisEvent::PlayerRootPropertiesChanged(_, diff) => {
+                if diff.is_empty() {
+                    return None;
+                }
+
+                diff.hash_values(&mut hasher);
+            }
+            MprisEvent::PlayerMetadataChanged(_, diff) => {
+                diff.changed_fields().hash(&mut hasher);
+                diff.new_metadata().hash_fields(&mut hasher);
+            }
+            MprisEvent::PlayerTrackChanged(_, metadata)
+            | MprisEvent::PlayerTrackStarted(_, metadata)
+            | MprisEvent::PlayerTrackFinished(_, metadata)
+            | MprisEvent::PlayerTrackScrobblable(_, metadata) => metadata.hash_fields(&mut hasher),
+            MprisEvent::PlayerTrackEnding(_, remaining) => remaining.hash(&mut hasher),
+            MprisEvent::PlayerPlaybackStatusChanged(_, old, new) => {
+                old.to_string().hash(&mut hasher);
+                new.to_string().hash(&mut hasher);
+            }
+            MprisEvent::PlayerCapabilitiesChanged(_, capabilities) => {
+                capabilities.hash(&mut hasher)
+            }
+            MprisEvent::PlayerPlaylistChanged(_, playlist) => {
+                playlist.id().hash(&mut hasher);
+                playlist.name().hash(&mut hasher);
+                playlist.icon().hash(&mut hasher);
+            }
+            MprisEvent::PlayerPosition(_, position) => position.as_secs().hash(&mut hasher),
+        }
+
+        Some(hasher.finish())
+    }
+
+    /// Gets the identity of the player this event is about.
+    pub fn identity(&self) -> &PlayerIdentity {
+        match self {
+            MprisEvent::PlayerAttached(player, _) => player.identity(),
+            MprisEvent::PlayerDetached(identity)
+            | MprisEvent::PlayerPropertiesChanged(identity, _)
+            | MprisEvent::PlayerMetadataChanged(identity, _)
+            | MprisEvent::PlayerTrackChanged(identity, _)
+            | MprisEvent::PlayerTrackStarted(identity, _)
+            | MprisEvent::PlayerTrackFinished(identity, _)
+            | MprisEvent::PlayerTrackScrobblable(identity, _)
+            | MprisEvent::PlayerTrackEnding(identity, _)
+            | MprisEvent::PlayerPlaybackStatusChanged(identity, _, _)
+            | MprisEvent::PlayerCapabilitiesChanged(identity, _)
+            | MprisEvent::PlayerRootPropertiesChanged(identity, _)
+            | MprisEvent::PlayerPlaylistChanged(identity, _)
+            | MprisEvent::PlayerSeeked(identity)
+            | MprisEvent::PlayerPosition(identity, _)
+            | MprisEvent::PlayerAutoPaused(identity, _) => identity,
+        }
+    }
 }
 
 /// Represents an MPRIS connection.
@@ -71,11 +334,11 @@ pub enum MprisEvent {
 /// # Example
 ///
 /// ```no_run
-/// use mpris::Mpris;
+/// use mprizzle::{Mpris, MprisEvent};
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     let mpris = Mpris::new(None).await?;
+///     let mut mpris = Mpris::new().await?;
 ///
 ///     // Start watching for mpris events.
 ///     mpris.watch();
@@ -85,14 +348,13 @@ pub enum MprisEvent {
 ///
 ///         match event_result {
 ///             Ok(event) => match event {
-///                 MprisEvent::PlayerAttached(player) => println!("ATTACHED = {:?}", player.identity().short()),
+///                 MprisEvent::PlayerAttached(player, _) => println!("ATTACHED = {:?}", player.identity().short()),
 ///                 MprisEvent::PlayerDetached(identity) => println!("DETACHED = {:?}", identity.short()),
+///                 _ => {}
 ///             },
 ///             Err(err) => panic!("{:?}", err),
 ///         }
 ///     }
-///
-///     Ok(())
 /// }
 /// ```
 #[derive(Debug)]
@@ -100,188 +362,1200 @@ pub struct Mpris {
     /// The underlying connection to D-Bus.
     connection: Arc<Mutex<Connection>>,
 
-    /// Event sender.
-    sender: mpsc::UnboundedSender<MprisResult<MprisEvent>>,
+    /// The bus to reconnect to when the connection breaks, None for a connection handed in.
+    bus: Option<BusKind>,
+
+    /// The connections to the extra buses, watched alongside the main one.
+    extra_connections: Vec<(BusKind, Arc<Mutex<Connection>>)>,
+
+    /// Event channel, bounded by the options.
+    queue: Arc<EventQueue>,
 
     /// Event receiver.
-    receiver: mpsc::UnboundedReceiver<MprisResult<MprisEvent>>,
+    receiver: EventReceiver,
+
+    /// Runtime counters of the players.
+    stats: Stats,
+
+    /// The options, shared with the watch tasks.
+    options: watch::Sender<MprisOptions>,
+
+    /// The events that can be sent out at all.
+    mask: EventMask,
+
+    /// The players that get watched and listed.
+    players: PlayerFilter,
+
+    /// The players attached, kept up to date by the watch task.
+    registry: SharedPlayers,
+
+    /// The event filter closure, shared with the watch tasks.
+    event_filter: SharedEventFilter,
+
+    /// The closures run on every event, shared with the watch tasks.
+    hooks: SharedEventHooks,
+
+    /// Follows the active player, shared with the watch tasks.
+    now_playing: NowPlayingTracker,
+
+    /// Hands a copy of every event to the subscriptions.
+    subscribers: broadcast::Sender<MprisResult<MprisEvent>>,
+
+    /// The watch task, while watching.
+    watch_handle: std::sync::Mutex<Option<WatchHandle>>,
+
+    /// Every task spawned, aborted when dropped.
+    tasks: WatchTasks,
 }
 
 impl Mpris {
     pub async fn new() -> MprisResult<Self> {
-        let session = Connection::session()
-            .await
-            .map_err(|err| MprisError::FailedToConnectDbus(err.to_string()))?;
+        Self::with_mask(EventMask::ALL).await
+    }
+
+    /// Creates a connection that only ever sends out the events in the mask.
+    ///
+    /// The watch tasks don't subscribe to the signals nor poll for the events left out,
+    /// see [`EventMask`].
+    pub async fn with_mask(mask: EventMask) -> MprisResult<Self> {
+        Self::builder().with_mask(mask).build().await
+    }
+
+    /// Sets up a connection before it connects, see [`MprisBuilder`].
+    pub fn builder() -> MprisBuilder {
+        MprisBuilder::new()
+    }
+
+    /// Creates a connection to another bus than the session one, see [`BusKind`].
+    pub async fn new_on(bus: BusKind) -> MprisResult<Self> {
+        Self::builder().with_bus(bus).build().await
+    }
 
-        let connection = Arc::new(Mutex::new(session));
+    /// Creates a connection on top of a zbus connection the application has already,
+    /// rather than opening a second one.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let connection = zbus::Connection::session().await?;
+    ///     let mpris = Mpris::with_connection(connection.clone()).await?;
+    ///
+    ///     for player in mpris.list_players().await? {
+    ///         println!("{}", player.identity().short());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn with_connection(connection: Connection) -> MprisResult<Self> {
+        Self::builder().with_connection(connection).build().await
+    }
+
+    pub(crate) fn build(
+        connection: Connection,
+        bus: Option<BusKind>,
+        extra_connections: Vec<(Connection, BusKind)>,
+        options: MprisOptions,
+        mask: EventMask,
+        players: PlayerFilter,
+        ranking: PlayerRanking,
+    ) -> Self {
+        let connection = Arc::new(Mutex::new(connection));
+
+        let (queue, receiver) = EventQueue::new();
+        queue.set_capacity(options.event_capacity().copied());
 
-        let (sender, receiver) = mpsc::unbounded_channel();
+        let subscribers = broadcast::channel(options.subscription_capacity()).0;
 
-        Ok(Self {
+        let extra_connections = extra_connections
+            .into_iter()
+            .map(|(connection, bus)| (bus, Arc::new(Mutex::new(connection))))
+            .collect();
+
+        Self {
             connection,
-            sender,
+            bus,
+            extra_connections,
+            queue,
             receiver,
+            stats: Stats::default(),
+            options: watch::channel(options).0,
+            mask,
+            players,
+            registry: SharedPlayers::default(),
+            event_filter: SharedEventFilter::default(),
+            hooks: SharedEventHooks::default(),
+            now_playing: NowPlayingTracker::new(ranking),
+            subscribers,
+            watch_handle: std::sync::Mutex::new(None),
+            tasks: WatchTasks::default(),
+        }
+    }
+
+    /// Start watching for mpris events, only sending out the ones the filter lets through.
+    ///
+    /// The filter replaces the one in the current [`MprisOptions`].
+    pub fn watch_filtered(&self, filter: EventFilter) -> WatchHandle {
+        self.update_options(|options| options.with_filter(filter));
+        self.watch()
+    }
+
+    /// Start watching for mpris events, using the current [`MprisOptions`].
+    ///
+    /// Only the first call starts watching, the later ones hand out the same [`WatchHandle`]
+    /// rather than sending out every event twice, until [`Mpris::stop`] gets called.
+    ///
+    /// A player restarting onto the same bus gets detached and attached again,
+    /// so the [`MprisPlayer`] kept around from before has to be swapped for the new one.
+    ///
+    /// So do all the players when the connection to D-Bus breaks: they get detached along
+    /// with an [`MprisError::ConnectionLost`] error, then attached again once reconnected,
+    /// retrying less and less often. A connection handed in through [`Mpris::with_connection`]
+    /// can't be reopened, the watch stops with that error instead.
+    pub fn watch(&self) -> WatchHandle {
+        let mut watch_handle = self
+            .watch_handle
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+
+        watch_handle
+            .get_or_insert_with(|| self.start_watching())
+            .clone()
+    }
+
+    /// Stops watching for mpris events, until [`Mpris::watch`] gets called again.
+    ///
+    /// Waits for the watch task and the player tasks to stop, then drops the events
+    /// still queued, so the next event received comes from the next watch. That one
+    /// starts over with a PlayerAttached per player, as the players may have changed
+    /// in between. A replay stops for good, the next watch watches the players.
+    pub async fn stop(&mut self) {
+        let watch_handle = self
+            .watch_handle
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+
+        if let Some(watch_handle) = watch_handle {
+            watch_handle.abort();
+            let _ = watch_handle.join().await;
+        }
+
+        while self.receiver.try_recv().is_some() {}
+        self.registry.lock().await.clear();
+        self.now_playing.clear();
+    }
+
+    /// Stops watching and waits for every task to stop, for a couple of seconds at most.
+    ///
+    /// Errors with [`MprisError::FailedToShutdown`], holding the errors the tasks stopped on
+    /// along the way and [`MprisError::ShutdownTimedOut`] if they didn't stop in time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    /// use tokio::signal::unix::{SignalKind, signal};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     let mut sigterm = signal(SignalKind::terminate())?;
+    ///
+    ///     mpris.watch();
+    ///
+    ///     loop {
+    ///         tokio::select! {
+    ///             _ = sigterm.recv() => break,
+    ///             event = mpris.recv() => println!("{:?}", event??.kind()),
+    ///         }
+    ///     }
+    ///
+    ///     mpris.shutdown().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn shutdown(mut self) -> MprisResult<()> {
+        let watch_handle = self
+            .watch_handle
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .take();
+
+        if let Some(watch_handle) = watch_handle {
+            watch_handle.abort();
+        }
+
+        let errors = self.tasks.join(SHUTDOWN_TIMEOUT).await;
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MprisError::FailedToShutdown(errors))
+        }
+    }
+
+    /// Takes the errors the watch tasks stopped on since the last call, panics included.
+    ///
+    /// A player task stopping early leaves that player without events, while the others
+    /// keep going. The errors get sent out as events too, this tells them apart from the
+    /// errors the tasks got past.
+    pub fn join_errors(&self) -> Vec<MprisError> {
+        self.tasks.errors()
+    }
+
+    /// Spawns the task watching the main bus, which spawns one task per extra bus
+    /// along with the one enforcing single playback.
+    fn start_watching(&self) -> WatchHandle {
+        let event_sender = self.sender();
+
+        // Stops every task at once, when the last player detached.
+        let idle = CancellationToken::new();
+
+        WatchHandle::spawn_in(&self.tasks, event_sender.clone(), |handle| {
+            let enforce = single_playback::enforce(
+                self.subscribers.clone(),
+                self.players(),
+                self.options.subscribe(),
+                event_sender.clone(),
+                self.mask,
+            );
+
+            let enforce_idle = idle.clone();
+            handle.adopt(WatchHandle::spawn_in(
+                &self.tasks,
+                event_sender.clone(),
+                |_| async move {
+                    tokio::select! {
+                        result = enforce => result,
+                        _ = enforce_idle.cancelled() => Ok(()),
+                    }
+                },
+            ));
+
+            // The extra buses stop along with the main one.
+            for (bus, connection) in &self.extra_connections {
+                let source = Some(bus.clone());
+                let connection = Arc::clone(connection);
+
+                handle.adopt(WatchHandle::spawn_in(
+                    &self.tasks,
+                    event_sender.clone(),
+                    |handle| {
+                        self.watch_bus(handle, idle.clone(), connection, source.clone(), source)
+                    },
+                ));
+            }
+
+            self.watch_bus(handle, idle, self.connection(), self.bus.clone(), None)
         })
     }
 
-    /// Start watching for mpris events.
-    pub fn watch(&self) {
-        let shared_connection = self.connection();
+    /// Creates the task watching for the players of a bus, which spawns one task per player.
+    ///
+    /// The bus gets reconnected to when given, the identities get tagged with the source.
+    /// Cancels `idle` once the last player of every bus detached, if the options say so.
+    fn watch_bus(
+        &self,
+        handle: WeakWatchHandle,
+        idle: CancellationToken,
+        shared_connection: Arc<Mutex<Connection>>,
+        bus: Option<BusKind>,
+        source: Option<BusKind>,
+    ) -> impl Future<Output = MprisResult<()>> + Send + 'static + use<> {
         let event_sender = self.sender();
+        let stats = self.stats.clone();
+        let options = self.options.subscribe();
+        let mask = self.mask;
+        let players = self.players.clone();
+        let registry = Arc::clone(&self.registry);
+        let tasks = self.tasks.clone();
 
-        // Creates a broadcast channel for indicating to a player,
-        // that they have been removed.
-        // This channel will be sending out full bus names.
-        let (close_sender, _) = broadcast::channel::<String>(69); // 69 for good measure.
-
-        tokio::spawn(async move {
-            // Creates a new dbus proxy.
-            let shared_conn = Arc::clone(&shared_connection);
-            let dbus_proxy = match proxies::create_dbus_proxy(shared_conn).await {
-                Ok(dbus_proxy) => dbus_proxy,
-                Err(err) => {
-                    event_sender.send(Err(err)).unwrap();
-                    return;
-                }
+        async move {
+            // Creates the identity of a player on this bus.
+            let identify = |name: String| {
+                PlayerIdentity::new(name).map(|identity| identity.with_source(source.clone()))
             };
 
-            // Creates a NameOwnerChanged signal stream.
-            let mut noc_stream = match dbus_proxy.receive_signal("NameOwnerChanged").await {
-                Ok(noc_stream) => noc_stream,
-                Err(err) => {
-                    event_sender
-                        .send(Err(MprisError::Other(format!(
-                            "Failed to create a stream for NameOwnerChanged: {err}"
-                        ))))
-                        .unwrap();
-
-                    return;
-                }
-            };
+            // Stops the player tasks once this one stops, however it does.
+            let cancel = CancellationToken::new();
+            let _cancel_guard = cancel.clone().drop_guard();
 
-            // Gets existing mpris player buses.
-            let buses: Vec<String> = match dbus_proxy.call("ListNames", &()).await {
-                Ok(buses) => buses,
-                Err(err) => {
-                    event_sender
-                        .send(Err(MprisError::FailedToCallFn(
+            // Cancels the task of a single player, once it gets detached.
+            let mut player_cancels: HashMap<PlayerIdentity, CancellationToken> = HashMap::new();
+
+            // Watches the bus, then watches it again after reconnecting each time the connection breaks.
+            loop {
+                // Creates a new dbus proxy.
+                let shared_conn = Arc::clone(&shared_connection);
+                let dbus_proxy = proxies::create_dbus_proxy(shared_conn).await?;
+
+                // Routes the signals of every player on this connection, stopped along with it.
+                let dispatcher_cancel = cancel.child_token();
+                let (dispatcher, dispatching) = SignalDispatcher::new(
+                    Arc::clone(&shared_connection),
+                    mask,
+                    dispatcher_cancel.clone(),
+                )
+                .await?;
+
+                handle.adopt(WatchHandle::spawn_in(&tasks, event_sender.clone(), |_| {
+                    dispatching
+                }));
+
+                // Creates a NameOwnerChanged signal stream, for the mpris buses only.
+                let mut noc_stream =
+                    proxies::receive_mpris_name_owner_changed(Arc::clone(&shared_connection))
+                        .await?;
+
+                // Gets existing mpris player buses.
+                let buses = match dbus_proxy.list_names().await {
+                    Ok(buses) => buses,
+                    Err(err) => {
+                        return Err(MprisError::FailedToCallFn(
                             "ListNames".into(),
                             err.to_string(),
-                        )))
-                        .unwrap();
+                        ));
+                    }
+                };
+
+                // Filter out mpris buses.
+                let existing_identities = buses
+                    .into_iter()
+                    .filter_map(|bus| {
+                        // Creates identity from bus.
+                        let identity = identify(bus.to_string()).ok()?;
+                        Some(identity)
+                    })
+                    .filter(|identity| players.matches(identity))
+                    .collect::<Vec<PlayerIdentity>>();
 
-                    return;
+                // Creates the existing players concurrently, a few at a time.
+                let mut attaching = stream::iter(existing_identities)
+                    .map(|identity| {
+                        let shared_conn = Arc::clone(&shared_connection);
+                        let stats = stats.clone();
+
+                        async move {
+                            MprisResult::Ok(
+                                MprisPlayer::new(shared_conn, identity)
+                                    .await?
+                                    .with_stats(stats),
+                            )
+                        }
+                    })
+                    .buffer_unordered(ATTACH_CONCURRENCY);
+
+                // Adds each player on shared players as soon as it's ready, its task sends out its PlayerAttached event.
+                while let Some(player) = attaching.next().await {
+                    let player = player?;
+                    let identity = player.identity().clone();
+
+                    // Registered before the event goes out, for the receiver to find it there.
+                    registry
+                        .lock()
+                        .await
+                        .insert(identity.clone(), Arc::new(player.clone()));
+
+                    // Watch this existing player for events.
+                    let player_cancel = cancel.child_token();
+                    player_cancels.insert(identity, player_cancel.clone());
+
+                    let task = player.watch_task(
+                        event_sender.clone(),
+                        player_cancel,
+                        options.clone(),
+                        mask,
+                        SignalSource::Shared(dispatcher.clone()),
+                        true,
+                    );
+
+                    handle.adopt(WatchHandle::spawn_in(&tasks, event_sender.clone(), |_| {
+                        task
+                    }));
                 }
-            };
 
-            // Filter out mpris buses.
-            let existing_identities = buses
-                .into_iter()
-                .filter_map(|bus| {
-                    // Creates identity from bus.
-                    let identity = PlayerIdentity::new(bus.to_string()).ok()?;
-                    Some(identity)
-                })
-                .collect::<Vec<PlayerIdentity>>();
-
-            // Loop over the existing players identity to add it on shared players and send out the PlayerAttached event.
-            for identity in existing_identities {
-                // Creates the player.
-                let shared_conn = Arc::clone(&shared_connection);
-                let player = match MprisPlayer::new(shared_conn, identity.clone()).await {
-                    Ok(player) => player,
-                    Err(err) => {
-                        event_sender.send(Err(err.into())).unwrap();
-                        return;
+                loop {
+                    tokio::select! {
+                        // Tells tokio::select to check for the result chronologically.
+                        // So it checks if event channel has been closed first, then the rest.
+                        biased;
+
+                        // Stop once the event channel has been closed.
+                        _ = event_sender.closed() => return Ok(()),
+
+                        // Stop once every player went away, from this bus or another one.
+                        _ = idle.cancelled() => return Ok(()),
+
+                        // Receive NameOwnerChanged signal, the stream ends along with the connection.
+                        signal = noc_stream.next() => {
+                            let Some(signal) = signal else {
+                                break;
+                            };
+
+                            if let Ok(args) = signal.args() {
+                                let name = args.name().to_string();
+                                let old_owner = args.old_owner().as_ref().map(|owner| owner.to_string()).unwrap_or_default();
+                                let new_owner = args.new_owner().as_ref().map(|owner| owner.to_string()).unwrap_or_default();
+
+                                // Skip the players left out by the builder.
+                                if identify(name.clone()).is_ok_and(|identity| !players.matches(&identity)) {
+                                    continue;
+                                }
+
+                                // There has been a mpris player detached, or its bus moved on to another owner.
+                                if !old_owner.is_empty() {
+                                    let identity = match identify(name.to_string()) {
+                                        Ok(identity) => identity,
+                                        Err(err) => {
+                                            return Err(MprisError::Other(format!("Failed to create a player identity on detached player: {name}: {err}")));
+                                        }
+                                    };
+
+                                    // Stops the async task of the player, unless it went away before it got attached.
+                                    if let Some(player_cancel) = player_cancels.remove(&identity) {
+                                        player_cancel.cancel();
+                                        registry.lock().await.remove(&identity);
+
+                                        // Send out the PlayerDetached event.
+                                        if mask.contains(EventMask::DETACH) && options.borrow().filter().matches_kind(EventKind::PlayerDetached)
+                                            && event_sender.send(Ok(MprisEvent::PlayerDetached(identity))).await.is_err()
+                                        {
+                                            return Ok(());
+                                        }
+
+                                        // Tells the receiver it was the last one, then stops every task.
+                                        if options.borrow().exit_when_idle() && registry.lock().await.is_empty() {
+                                            let _ = event_sender.send(Err(MprisError::AllPlayersGone)).await;
+                                            idle.cancel();
+
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+
+                                // There has been a new mpris player, or a restarted one that took over the bus.
+                                if !new_owner.is_empty() {
+                                    // Creates the player identity.
+                                    let identity = identify(name.to_string())?;
+
+                                    // A player showing up while the buses got listed is attached already.
+                                    if player_cancels.contains_key(&identity) {
+                                        continue;
+                                    }
+
+                                    // Creates the player itself with the shared connection.
+                                    let shared_conn = Arc::clone(&shared_connection);
+                                    let player = match MprisPlayer::new(shared_conn, identity.clone()).await {
+                                        Ok(player) => player.with_stats(stats.clone()),
+                                        Err(err) => {
+                                            return Err(err);
+                                        }
+                                    };
+
+                                    // Registered before the event goes out, for the receiver to find it there.
+                                    registry.lock().await.insert(identity.clone(), Arc::new(player.clone()));
+
+                                    // Watch this newly created player for events, its task sends out its PlayerAttached event.
+                                    let player_cancel = cancel.child_token();
+                                    player_cancels.insert(identity, player_cancel.clone());
+
+                                    let task = player.watch_task(event_sender.clone(), player_cancel, options.clone(), mask, SignalSource::Shared(dispatcher.clone()), true);
+                                    handle.adopt(WatchHandle::spawn_in(&tasks, event_sender.clone(), |_| task));
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // The players went away along with the connection.
+                dispatcher_cancel.cancel();
+
+                registry
+                    .lock()
+                    .await
+                    .retain(|identity, _| identity.source() != source.as_ref());
+
+                for (identity, player_cancel) in player_cancels.drain() {
+                    player_cancel.cancel();
+
+                    if mask.contains(EventMask::DETACH)
+                        && options
+                            .borrow()
+                            .filter()
+                            .matches_kind(EventKind::PlayerDetached)
+                        && event_sender
+                            .send(Ok(MprisEvent::PlayerDetached(identity)))
+                            .await
+                            .is_err()
+                    {
+                        return Ok(());
                     }
+                }
+
+                // A connection handed in by the application can't be opened again.
+                let Some(bus) = &bus else {
+                    return Err(MprisError::ConnectionLost);
                 };
 
-                // Watch this existing player for events.
-                player.watch(event_sender.clone(), close_sender.subscribe());
+                if event_sender
+                    .send(Err(MprisError::ConnectionLost))
+                    .await
+                    .is_err()
+                {
+                    return Ok(());
+                }
 
-                // Send out PlayerAttached event along with the player.
-                event_sender
-                    .send(Ok(MprisEvent::PlayerAttached(player)))
-                    .unwrap();
+                // Waits longer after each failed attempt, the bus can take a while to come back.
+                let mut backoff = RECONNECT_BACKOFF_MIN;
+                let connection = loop {
+                    tokio::select! {
+                        biased;
+
+                        _ = event_sender.closed() => return Ok(()),
+                        _ = idle.cancelled() => return Ok(()),
+                        _ = tokio::time::sleep(backoff) => {}
+                    }
+
+                    match bus.connect().await {
+                        Ok(connection) => break connection,
+                        Err(_) => backoff = (backoff * 2).min(RECONNECT_BACKOFF_MAX),
+                    }
+                };
+
+                // The players and the proxies created from now on use the new connection.
+                *shared_connection.lock().await = connection;
             }
+        }
+    }
+
+    /// Creates a connection replaying the events recorded by an [`crate::EventRecorder`],
+    /// rather than watching the players.
+    ///
+    /// The events come out with the same gaps between them as when they got recorded,
+    /// going through the options like the watched ones. The replayed players have no object
+    /// behind them on the bus, so calling them errors, what they were at is in the
+    /// [`PlayerSnapshot`] and the events. Nothing gets called through the connection,
+    /// it only backs those players, so it can be one to any bus, like a private `dbus-daemon`.
+    /// [`Mpris::watch`] hands out the [`WatchHandle`] of the replay.
+    ///
+    /// See [`MprisBuilder::replay`] for replaying with other options.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let connection = zbus::Connection::session().await?;
+    ///     let mut mpris = Mpris::replay("events.jsonl", connection).await?;
+    ///
+    ///     loop {
+    ///         match mpris.recv().await? {
+    ///             Ok(event) => println!("{}: {:?}", event.identity().short(), event.kind()),
+    ///             Err(err) => eprintln!("{err}"),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "recording")]
+    pub async fn replay(
+        path: impl AsRef<std::path::Path>,
+        connection: Connection,
+    ) -> MprisResult<Self> {
+        Self::builder()
+            .with_connection(connection)
+            .replay(path)
+            .await
+    }
+
+    /// Starts replaying the records, in place of watching the players.
+    #[cfg(feature = "recording")]
+    pub(crate) fn start_replay(&self, records: Vec<crate::recording::Record>) {
+        let connection = self.connection();
+        let event_sender = self.sender();
+        let stats = self.stats.clone();
+        let options = self.options.subscribe();
+
+        let handle = WatchHandle::spawn_in(&self.tasks, event_sender.clone(), |_| async move {
+            let Some(first) = records.first().map(|record| record.at()) else {
+                return Ok(());
+            };
+
+            let started = tokio::time::Instant::now();
+
+            for record in records {
+                let offset = record.at().duration_since(first).unwrap_or_default();
 
-            loop {
                 tokio::select! {
-                    // Tells tokio::select to check for the result chronologically.
-                    // So it checks if event channel has been closed first, then the rest.
                     biased;
 
-                    // Break out of the loop if the event channel has been closed.
-                    _ = event_sender.closed() => break,
+                    // Stop replaying once the event channel has been closed.
+                    _ = event_sender.closed() => return Ok(()),
 
-                    // Receive NameOwnerChanged signal.
-                    Some(signal) = noc_stream.next() => {
-                        if let Ok((name, old_owner, new_owner)) = signal.body().deserialize::<(String, String, String)>() {
-                            // Only accepts mpris signals.
-                            if !name.starts_with(DBUS_MPRIS_INTERFACE_NAME) {
-                                continue;
-                            }
+                    _ = tokio::time::sleep_until(started + offset) => {}
+                }
 
-                            // There has been a new mpris player.
-                            if old_owner.is_empty() && !new_owner.is_empty() {
-                                // Creates the player identity.
-                                let identity = match PlayerIdentity::new(name.to_string()) {
-                                    Ok(identity) => identity,
-                                    Err(err) => {
-                                        event_sender.send(Err(err.into())).unwrap();
-                                        return;
-                                    }
-                                };
-
-                                // Creates the player itself with the shared connection.
-                                let shared_conn = Arc::clone(&shared_connection);
-                                let player = match MprisPlayer::new(shared_conn, identity.clone()).await {
-                                    Ok(player) => player,
-                                    Err(err) => {
-                                        event_sender.send(Err(err.into())).unwrap();
-                                        return;
-                                    }
-                                };
+                let event = record
+                    .into_event(Arc::clone(&connection), stats.clone())
+                    .await;
 
-                                // Watch this newly created player for events.
-                                player.watch(event_sender.clone(), close_sender.subscribe());
+                if let Ok(event) = &event
+                    && !options.borrow().filter().matches(event)
+                {
+                    continue;
+                }
 
-                                // Send out PlayerAttached event along with the player.
-                                event_sender.send(Ok(MprisEvent::PlayerAttached(player))).unwrap();
-                            }
+                if event_sender.send(event).await.is_err() {
+                    return Ok(());
+                }
+            }
 
-                            // There has been a mpris player detached.
-                            if !old_owner.is_empty() && new_owner.is_empty() {
-                                let identity = match PlayerIdentity::new(name.to_string()) {
-                                    Ok(identity) => identity,
-                                    Err(err) => {
-                                        event_sender
-                                            .send(Err(MprisError::Other(format!("Failed to create a player identity on detached player: {err}"))))
-                                            .unwrap();
+            Ok(())
+        });
 
-                                        return;
-                                    }
-                                };
+        // Nothing gets watched on top of the recording.
+        *self
+            .watch_handle
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(handle);
+    }
 
-                                // Sends out the event to close the async task of player.
-                                close_sender.send(name).unwrap();
+    /// Lists the mpris players currently present on the bus.
+    ///
+    /// Unlike [`Mpris::watch`], this does not spawn anything and is meant for
+    /// one-shot queries, like the ones made by the `mprizzle` CLI.
+    pub async fn list_players(&self) -> MprisResult<Vec<MprisPlayer>> {
+        let mut players = Vec::new();
 
-                                // Send out the PlayerDetached event.
-                                event_sender.send(Ok(MprisEvent::PlayerDetached(identity))).unwrap();
-                            }
-                        }
-                    }
+        for (source, connection) in self.connections() {
+            let dbus_proxy = proxies::create_dbus_proxy(Arc::clone(&connection)).await?;
+
+            // Gets existing mpris player buses.
+            let buses = dbus_proxy
+                .list_names()
+                .await
+                .map_err(|err| MprisError::FailedToCallFn("ListNames".into(), err.to_string()))?;
+
+            for bus in buses {
+                // Skip the buses that are not mpris players.
+                let Ok(identity) = PlayerIdentity::new(bus.to_string()) else {
+                    continue;
+                };
+
+                let identity = identity.with_source(source.clone());
+
+                // Skip the players left out by the builder.
+                if !self.players.matches(&identity) {
+                    continue;
                 }
+
+                let player = MprisPlayer::new(Arc::clone(&connection), identity).await?;
+                players.push(player.with_stats(self.stats.clone()));
             }
+        }
+
+        Ok(players)
+    }
+
+    /// Gets the players attached so far, kept up to date by [`Mpris::watch`].
+    ///
+    /// A player is in there by the time its PlayerAttached event comes out, and gone by the time
+    /// its PlayerDetached one does, whether or not those events get sent out. The map stays empty
+    /// until watching, unlike [`Mpris::list_players`] which asks the bus.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::{Mpris, MprisEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     let players = mpris.players();
+    ///
+    ///     mpris.watch();
+    ///
+    ///     while let Ok(event) = mpris.recv().await? {
+    ///         if let MprisEvent::PlayerPropertiesChanged(identity, _) = event {
+    ///             if let Some(player) = players.lock().await.get(&identity) {
+    ///                 println!("{}: {:?}", identity.short(), player.playback_status().await?);
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn players(&self) -> SharedPlayers {
+        Arc::clone(&self.registry)
+    }
+
+    /// Gets a controller sending each call to every attached player, see [`AllPlayers`].
+    pub fn all(&self) -> AllPlayers {
+        AllPlayers::new(self.players())
+    }
+
+    /// Gets an attached player by its short name, its name or its bus name,
+    /// see [`PlayerIdentity::matches_name`].
+    ///
+    /// A full name wins over a short one shared by several instances,
+    /// the instances otherwise get picked in bus name order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     mpris.watch();
+    ///
+    ///     while let Ok(_) = mpris.recv().await? {
+    ///         if let Some(spotify) = mpris.get_player("spotify").await {
+    ///             println!("{:?}", spotify.playback_status().await?);
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_player(&self, name: &str) -> Option<Arc<MprisPlayer>> {
+        self.registry
+            .lock()
+            .await
+            .iter()
+            .filter(|(identity, _)| identity.matches_name(name))
+            .min_by_key(|(identity, _)| name_rank(identity, name))
+            .map(|(_, player)| Arc::clone(player))
+    }
+
+    /// Waits for a player matching the name to be on the bus, for the given time at most,
+    /// see [`PlayerIdentity::matches_name`].
+    ///
+    /// Resolves right away if the player is there already, otherwise as soon as it shows up.
+    /// Unlike [`Mpris::get_player`], this doesn't need [`Mpris::watch`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::{process::Command, time::Duration};
+    ///
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mpris = Mpris::new().await?;
+    ///
+    ///     Command::new("spotify").spawn()?;
+    ///     let spotify = mpris.wait_for_player("spotify", Duration::from_secs(10)).await?;
+    ///     spotify.play().await?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wait_for_player(&self, name: &str, timeout: Duration) -> MprisResult<MprisPlayer> {
+        let identity = tokio::time::timeout(timeout, self.find_on_bus(name))
+            .await
+            .map_err(|_| MprisError::PlayerWaitTimedOut(name.to_string(), timeout))??;
+
+        let player = MprisPlayer::new(self.connection_of(&identity), identity).await?;
+        Ok(player.with_stats(self.stats.clone()))
+    }
+
+    /// Finds the identity of a player matching the name, waiting for it to show up if needed.
+    async fn find_on_bus(&self, name: &str) -> MprisResult<PlayerIdentity> {
+        let mut noc_streams = Vec::new();
+        let mut existing = Vec::new();
+
+        for (source, connection) in self.connections() {
+            let dbus_proxy = proxies::create_dbus_proxy(Arc::clone(&connection)).await?;
+
+            // Subscribed before listing the buses, so a player showing up in between isn't missed.
+            let noc_stream = proxies::receive_mpris_name_owner_changed(connection).await?;
+
+            let buses = dbus_proxy
+                .list_names()
+                .await
+                .map_err(|err| MprisError::FailedToCallFn("ListNames".into(), err.to_string()))?;
+
+            existing.extend(
+                buses
+                    .into_iter()
+                    .filter_map(|bus| PlayerIdentity::new(bus.to_string()).ok())
+                    .map(|identity| identity.with_source(source.clone())),
+            );
+
+            noc_streams.push(noc_stream.map(move |signal| (source.clone(), signal)));
+        }
+
+        // The first of the equal ones is on the main bus.
+        let existing = existing
+            .into_iter()
+            .filter(|identity| self.players.matches(identity) && identity.matches_name(name))
+            .min_by(|a, b| name_rank(a, name).cmp(&name_rank(b, name)));
+
+        if let Some(identity) = existing {
+            return Ok(identity);
+        }
+
+        let mut noc_stream = stream::select_all(noc_streams);
+        while let Some((source, signal)) = noc_stream.next().await {
+            let Ok(args) = signal.args() else {
+                continue;
+            };
+
+            // Only a bus getting an owner means a player showed up.
+            if args.new_owner().is_none() {
+                continue;
+            }
+
+            let Ok(identity) = PlayerIdentity::new(args.name().to_string()) else {
+                continue;
+            };
+
+            let identity = identity.with_source(source);
+            if self.players.matches(&identity) && identity.matches_name(name) {
+                return Ok(identity);
+            }
+        }
+
+        // The streams end along with the connections.
+        Err(MprisError::ConnectionLost)
+    }
+
+    /// Gets the first attached player the closure returns `true` for, in bus name order.
+    pub async fn find_player(
+        &self,
+        predicate: impl Fn(&MprisPlayer) -> bool,
+    ) -> Option<Arc<MprisPlayer>> {
+        self.registry
+            .lock()
+            .await
+            .iter()
+            .filter(|(_, player)| predicate(player))
+            .min_by_key(|(identity, _)| identity.bus())
+            .map(|(_, player)| Arc::clone(player))
+    }
+
+    /// Fetches the metadata of every mpris player on the bus, concurrently.
+    ///
+    /// A player failing to give its metadata doesn't fail the others,
+    /// so each one gets its own result.
+    pub async fn all_metadata(
+        &self,
+    ) -> MprisResult<HashMap<PlayerIdentity, MprisResult<PlayerMetadata<'static>>>> {
+        let players = self.list_players().await?;
+
+        let metadata = future::join_all(players.iter().map(|player| player.metadata())).await;
+
+        Ok(players
+            .iter()
+            .map(|player| player.identity().clone())
+            .zip(metadata)
+            .collect())
+    }
+
+    /// Only sends out the events the closure returns `true` for, replacing the previous one.
+    ///
+    /// The closure runs in the watch tasks before the events enter the channel, so the
+    /// dropped ones never wake up [`Mpris::recv`]. It should be quick, and must not set
+    /// the event filter itself. The errors always get through.
+    ///
+    /// ```no_run
+    /// use mprizzle::{Mpris, MprisEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mpris = Mpris::new().await?;
+    ///
+    ///     // Drop everything coming from the browsers.
+    ///     mpris.set_event_filter(|event: &MprisEvent| {
+    ///         !["firefox", "chromium"].contains(&event.identity().short())
+    ///     });
+    ///
+    ///     mpris.watch();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn set_event_filter(&self, filter: impl Fn(&MprisEvent) -> bool + Send + 'static) {
+        self.event_filter.set(Some(Box::new(filter)));
+    }
+
+    /// Lets every event through again, undoing [`Mpris::set_event_filter`].
+    pub fn clear_event_filter(&self) {
+        self.event_filter.set(None);
+    }
+
+    /// Adds a closure run on every event right before it gets sent out, after the ones already added.
+    ///
+    /// Handy for logging, metrics or notifications, without owning the receiver nor
+    /// subscribing. Like the event filter closure, the hooks run in the watch tasks
+    /// so they should be quick, and only see the events let through.
+    ///
+    /// ```no_run
+    /// use std::sync::{
+    ///     Arc,
+    ///     atomic::{AtomicUsize, Ordering},
+    /// };
+    ///
+    /// use mprizzle::{Mpris, MprisEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mpris = Mpris::new().await?;
+    ///     let seen = Arc::new(AtomicUsize::new(0));
+    ///
+    ///     let counter = Arc::clone(&seen);
+    ///     mpris.add_hook(move |_: &MprisEvent| {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     });
+    ///
+    ///     mpris.add_hook(|event: &MprisEvent| println!("{:?}", event.kind()));
+    ///     mpris.watch();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn add_hook(&self, hook: impl Fn(&MprisEvent) + Send + Sync + 'static) {
+        self.hooks.add(Box::new(hook));
+    }
+
+    /// Removes every hook added with [`Mpris::add_hook`].
+    pub fn clear_hooks(&self) {
+        self.hooks.clear();
+    }
+
+    /// Gets the events that can be sent out at all.
+    pub fn mask(&self) -> EventMask {
+        self.mask
+    }
+
+    /// Gets the current options.
+    pub fn options(&self) -> MprisOptions {
+        self.options.borrow().clone()
+    }
+
+    /// Replaces the options, applying them to the running watch tasks.
+    pub fn set_options(&self, options: MprisOptions) {
+        self.queue.set_capacity(options.event_capacity().copied());
+        self.options.send_replace(options);
+    }
+
+    /// Changes the options, applying them to the running watch tasks.
+    pub fn update_options(&self, update: impl FnOnce(MprisOptions) -> MprisOptions) {
+        self.options.send_modify(|options| {
+            *options = update(options.clone());
+            self.queue.set_capacity(options.event_capacity().copied());
         });
     }
 
+    /// Gets the runtime counters of every player seen so far, see [`PlayerStats`].
+    pub fn stats(&self) -> HashMap<PlayerIdentity, PlayerStats> {
+        self.stats.snapshot()
+    }
+
     /// Recieve mpris events.
     pub async fn recv(&mut self) -> MprisResult<MprisResult<MprisEvent>> {
+        Ok(self.receiver.recv().await.map(EventEnvelope::into_event))
+    }
+
+    /// Recieve mpris events, along with when they happened and their sequence number.
+    ///
+    /// Handy for the history and scrobbling code processing the events late, see
+    /// [`EventEnvelope::received_at`], and for passing the events on, see [`EventEnvelope::sequence`].
+    pub async fn recv_envelope(&mut self) -> MprisResult<MprisResult<EventEnvelope>> {
+        Ok(self.receiver.recv().await)
+    }
+
+    /// Recieve all the queued mpris events at once, up to the limit, waiting for one if there's none.
+    ///
+    /// Appends them to the buffer and returns how many there were, zero only for a zero limit.
+    /// Mirrors [`mpsc::UnboundedReceiver::recv_many`], for the consumers coalescing their redraws.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     mpris.watch();
+    ///
+    ///     let mut events = Vec::new();
+    ///     loop {
+    ///         mpris.recv_many(&mut events, 64).await;
+    ///
+    ///         for event in events.drain(..) {
+    ///             println!("{:?}", event?.kind());
+    ///         }
+    ///
+    ///         // Redraw once for the whole batch.
+    ///     }
+    /// }
+    /// ```
+    pub async fn recv_many(
+        &mut self,
+        buffer: &mut Vec<MprisResult<MprisEvent>>,
+        limit: usize,
+    ) -> usize {
         self.receiver
-            .recv()
+            .recv_many(buffer, limit, |result| {
+                result.map(EventEnvelope::into_event)
+            })
             .await
-            .ok_or(MprisError::FailedToRecvEvent)
+    }
+
+    /// Recieve an mpris event if there's one queued, without waiting for it.
+    ///
+    /// Mirrors [`mpsc::UnboundedReceiver::try_recv`], for the loops that can't await,
+    /// like the frame loops of games and immediate mode GUIs. The channel never
+    /// disconnects, so the error is always [`TryRecvError::Empty`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     mpris.watch();
+    ///
+    ///     loop {
+    ///         while let Ok(event) = mpris.try_recv() {
+    ///             println!("{:?}", event?.kind());
+    ///         }
+    ///
+    ///         // Draw the frame.
+    ///         # break;
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn try_recv(&mut self) -> Result<MprisResult<MprisEvent>, TryRecvError> {
+        self.receiver
+            .try_recv()
+            .map(|result| result.map(EventEnvelope::into_event))
+            .ok_or(TryRecvError::Empty)
+    }
+
+    /// Recieve mpris events, giving up after the timeout with `Ok(None)`.
+    ///
+    /// Made for the consumers redrawing on a timer regardless of the events, like status bars.
+    /// Unlike [`Mpris::recv`], the errors sent out by the watch tasks are returned as is.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     mpris.watch();
+    ///
+    ///     loop {
+    ///         if let Some(event) = mpris.recv_timeout(Duration::from_secs(1)).await? {
+    ///             println!("{:?}", event.kind());
+    ///         }
+    ///
+    ///         // Redraw.
+    ///     }
+    /// }
+    /// ```
+    pub async fn recv_timeout(&mut self, timeout: Duration) -> MprisResult<Option<MprisEvent>> {
+        match tokio::time::timeout(timeout, self.receiver.recv()).await {
+            Ok(result) => result.map(|envelope| Some(envelope.into_event())),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Subscribes to every event sent out from now on, next to [`Mpris::recv`].
+    ///
+    /// Each subscription gets its own copy of the events, so several components (a UI,
+    /// a scrobbler, a logger) can each follow the full event stream. A subscription falling
+    /// more than [`MprisOptions::with_subscription_capacity`] events behind skips the oldest ones,
+    /// see [`EventSubscription::recv`].
+    ///
+    /// The events keep being queued for [`Mpris::recv`] too, the watch tasks stop once
+    /// the [`Mpris`] gets dropped.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::{Mpris, MprisEvent};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut mpris = Mpris::new().await?;
+    ///     let mut logger = mpris.subscribe();
+    ///
+    ///     tokio::spawn(async move {
+    ///         while let Ok(event) = logger.recv().await {
+    ///             if let Ok(event) = event {
+    ///                 println!("{:?}", event.kind());
+    ///             }
+    ///         }
+    ///     });
+    ///
+    ///     mpris.watch();
+    ///
+    ///     while let Ok(event) = mpris.recv().await? {
+    ///         if let MprisEvent::PlayerAttached(player, _) = event {
+    ///             println!("{}", player.identity().short());
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn subscribe(&self) -> EventSubscription {
+        EventSubscription::new(self.subscribers.subscribe())
+    }
+
+    /// Follows what the active player is at, updated as the events go by.
+    ///
+    /// The active player is the one that started playing last among the playing ones,
+    /// or the one that played last when none is, None without any player. The builder can
+    /// rank the players otherwise, see [`MprisBuilder::with_ranking`]. The value only
+    /// changes along the events let through, so the metadata and the position need
+    /// [`Mpris::watch`] with an [`EventMask`] and a filter keeping them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use mprizzle::Mpris;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mpris = Mpris::new().await?;
+    ///     let mut now_playing = mpris.now_playing();
+    ///
+    ///     mpris.watch();
+    ///
+    ///     while now_playing.changed().await.is_ok() {
+    ///         if let Some(now_playing) = &*now_playing.borrow() {
+    ///             let title = now_playing.metadata().and_then(|metadata| metadata.title().ok().flatten());
+    ///             println!("{}: {title:?} at {:?}", now_playing.identity().short(), now_playing.position());
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn now_playing(&self) -> watch::Receiver<Option<NowPlaying>> {
+        self.now_playing.subscribe()
     }
 
     /// Gets the shared mpris connection.
@@ -289,8 +1563,133 @@ impl Mpris {
         Arc::clone(&self.connection)
     }
 
+    /// Gets the connection of every bus along with its source, the main one first.
+    fn connections(&self) -> Vec<(Option<BusKind>, Arc<Mutex<Connection>>)> {
+        let extra = self
+            .extra_connections
+            .iter()
+            .map(|(bus, connection)| (Some(bus.clone()), Arc::clone(connection)));
+
+        std::iter::once((None, self.connection()))
+            .chain(extra)
+            .collect()
+    }
+
+    /// Gets the connection to the bus the player is on.
+    fn connection_of(&self, identity: &PlayerIdentity) -> Arc<Mutex<Connection>> {
+        self.extra_connections
+            .iter()
+            .find(|(bus, _)| identity.source() == Some(bus))
+            .map_or_else(
+                || self.connection(),
+                |(_, connection)| Arc::clone(connection),
+            )
+    }
+
     /// Gets the cloned event sender.
-    fn sender(&self) -> mpsc::UnboundedSender<MprisResult<MprisEvent>> {
-        self.sender.clone()
+    fn sender(&self) -> EventSender {
+        EventSender::queue(Arc::clone(&self.queue), self.stats.clone())
+            .with_options(self.options.subscribe())
+            .with_filter(self.event_filter.clone())
+            .with_hooks(self.hooks.clone())
+            .with_now_playing(self.now_playing.clone())
+            .with_subscribers(self.subscribers.clone())
+    }
+}
+
+impl Drop for Mpris {
+    /// Stops the watch task and the player tasks right away,
+    /// even with clones of the [`WatchHandle`] still around.
+    fn drop(&mut self) {
+        if let Some(watch_handle) = self
+            .watch_handle
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .take()
+        {
+            watch_handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+    };
+
+    use super::*;
+
+    /// A bus of its own, so the tests don't need a session bus.
+    struct PrivateBus {
+        daemon: Child,
+        address: String,
+    }
+
+    impl PrivateBus {
+        fn start() -> Self {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .expect("dbus-daemon has to be installed");
+
+            let mut address = String::new();
+            let stdout = daemon.stdout.take().unwrap();
+            BufReader::new(stdout).read_line(&mut address).unwrap();
+
+            Self {
+                daemon,
+                address: address.trim().to_string(),
+            }
+        }
+
+        fn bus(&self) -> BusKind {
+            BusKind::Address(self.address.clone())
+        }
+    }
+
+    impl Drop for PrivateBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    #[tokio::test]
+    async fn watch_twice_attaches_once() {
+        let private_bus = PrivateBus::start();
+
+        let bus = "org.mpris.MediaPlayer2.mprizzle_test";
+        let player = zbus::connection::Builder::address(private_bus.address.as_str())
+            .unwrap()
+            .name(bus)
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+
+        let mut mpris = Mpris::builder()
+            .with_bus(private_bus.bus())
+            .build()
+            .await
+            .unwrap();
+
+        let first = mpris.watch();
+        let second = mpris.watch();
+        assert!(first.ptr_eq(&second));
+
+        let mut attached = 0;
+        while let Ok(event) = tokio::time::timeout(Duration::from_millis(500), mpris.recv()).await {
+            if let Ok(Ok(MprisEvent::PlayerAttached(player, _))) = event
+                && player.identity().bus() == bus
+            {
+                attached += 1;
+            }
+        }
+
+        assert_eq!(attached, 1);
+        drop(player);
     }
 }
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::watch;
+
+use crate::{MprisEvent, PlaybackStatus, PlayerIdentity, PlayerMetadata, builder::PlayerRanking};
+
+/// What the active player is at, as followed by [`crate::Mpris::now_playing`].
+#[derive(Debug, Clone)]
+pub struct NowPlaying {
+    identity: PlayerIdentity,
+    metadata: Option<PlayerMetadata<'static>>,
+    status: Option<PlaybackStatus>,
+    position: Duration,
+}
+
+impl NowPlaying {
+    /// Gets the identity of the player.
+    pub fn identity(&self) -> &PlayerIdentity {
+        &self.identity
+    }
+
+    /// Gets the metadata of the current track, None until the player told it.
+    pub fn metadata(&self) -> Option<&PlayerMetadata<'static>> {
+        self.metadata.as_ref()
+    }
+
+    /// Gets the playback status, None until the player told it.
+    pub fn status(&self) -> Option<&PlaybackStatus> {
+        self.status.as_ref()
+    }
+
+    /// Gets the last position sent out for the player.
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+}
+
+/// Follows the events to keep [`NowPlaying`] up to date, for the active player.
+///
+/// The active player is the one ranked highest by [`crate::MprisBuilder::with_ranking`].
+/// By default, or among the ones ranked equal, it's the one that started playing last
+/// among the playing ones, or the one that played last when none is.
+#[derive(Debug, Clone)]
+pub(crate) struct NowPlayingTracker(Arc<TrackerState>);
+
+#[derive(Debug)]
+struct TrackerState {
+    players: Mutex<Players>,
+    ranking: PlayerRanking,
+    sender: watch::Sender<Option<NowPlaying>>,
+}
+
+#[derive(Debug, Default)]
+struct Players {
+    known: HashMap<PlayerIdentity, (NowPlaying, u64)>,
+
+    /// Bumped each time a player starts playing, to tell which one did last.
+    plays: u64,
+}
+
+impl NowPlayingTracker {
+    pub(crate) fn new(ranking: PlayerRanking) -> Self {
+        Self(Arc::new(TrackerState {
+            players: Mutex::new(Players::default()),
+            ranking,
+            sender: watch::channel(None).0,
+        }))
+    }
+
+    /// Subscribes to the active player.
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Option<NowPlaying>> {
+        self.0.sender.subscribe()
+    }
+
+    /// Forgets the players, as they get attached again on the next watch.
+    pub(crate) fn clear(&self) {
+        let mut players = self.0.players.lock().unwrap_or_else(|err| err.into_inner());
+        players.known.clear();
+        drop(players);
+
+        self.0
+            .sender
+            .send_if_modified(|now_playing| now_playing.take().is_some());
+    }
+
+    /// Takes in the event, waking up the subscribers only when the active player changed.
+    pub(crate) fn track(&self, event: &MprisEvent) {
+        let mut players = self.0.players.lock().unwrap_or_else(|err| err.into_inner());
+        let ranking = &self.0.ranking;
+        let before = players.active(ranking);
+        let identity = event.identity();
+
+        match event {
+            MprisEvent::PlayerAttached(_, snapshot) => {
+                let now_playing = NowPlaying {
+                    identity: identity.clone(),
+                    metadata: snapshot.metadata().cloned(),
+                    status: snapshot.playback_status().cloned(),
+                    position: Duration::ZERO,
+                };
+
+                let plays = players.played(snapshot.playback_status());
+                players.known.insert(identity.clone(), (now_playing, plays));
+            }
+            MprisEvent::PlayerDetached(_) => {
+                players.known.remove(identity);
+            }
+            MprisEvent::PlayerMetadataChanged(_, diff) => {
+                players.update(identity, |now_playing| {
+                    now_playing.metadata = Some(diff.new_metadata().clone());
+                });
+            }
+            MprisEvent::PlayerTrackChanged(_, metadata) => {
+                players.update(identity, |now_playing| {
+                    now_playing.metadata = Some(metadata.clone());
+                });
+            }
+            MprisEvent::PlayerPlaybackStatusChanged(_, _, status) => {
+                let plays = players.played(Some(status));
+                if let Some((now_playing, last_played)) = players.known.get_mut(identity) {
+                    now_playing.status = Some(status.clone());
+                    *last_played = plays.max(*last_played);
+                }
+            }
+            MprisEvent::PlayerPosition(_, position) => {
+                players.update(identity, |now_playing| now_playing.position = *position);
+            }
+            _ => return,
+        }
+
+        let after = players.active(ranking);
+
+        // Another player's change leaves the active one as it was.
+        if before.as_ref() != after.as_ref() || after.as_ref() == Some(identity) {
+            let now_playing = after
+                .and_then(|active| players.known.get(&active))
+                .map(|(now_playing, _)| now_playing.clone());
+
+            self.0.sender.send_replace(now_playing);
+        }
+    }
+}
+
+impl Players {
+    /// Gets the identity of the active player.
+    fn active(&self, ranking: &PlayerRanking) -> Option<PlayerIdentity> {
+        let rank = |(now_playing, last_played): &(NowPlaying, u64)| {
+            (
+                now_playing.status == Some(PlaybackStatus::Playing),
+                *last_played,
+            )
+        };
+
+        // The bus breaks the ties, so the active player doesn't flip between the idle ones.
+        self.known
+            .values()
+            .max_by(|a, b| {
+                ranking
+                    .compare(&a.0, &b.0)
+                    .then_with(|| rank(a).cmp(&rank(b)))
+                    .then_with(|| b.0.identity.bus().cmp(a.0.identity.bus()))
+            })
+            .map(|(now_playing, _)| now_playing.identity.clone())
+    }
+
+    /// Counts a play for the players starting to play, zero for the others.
+    fn played(&mut self, status: Option<&PlaybackStatus>) -> u64 {
+        match status {
+            Some(PlaybackStatus::Playing) => {
+                self.plays += 1;
+                self.plays
+            }
+            _ => 0,
+        }
+    }
+
+    fn update(&mut self, identity: &PlayerIdentity, update: impl FnOnce(&mut NowPlaying)) {
+        if let Some((now_playing, _)) = self.known.get_mut(identity) {
+            update(now_playing);
+        }
+    }
+}
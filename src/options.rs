@@ -0,0 +1,430 @@
+use std::time::Duration;
+
+use crate::{EventFilter, TrackIdentity};
+
+/// Polls the players slowly mid-track, and quickly close to the end of the track,
+/// see [`MprisOptions::with_adaptive_polling`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptivePolling {
+    /// The poll interval mid-track, or when not playing.
+    slow: Duration,
+
+    /// The poll interval close to the end of the track.
+    fast: Duration,
+
+    /// How close to the end of the track the fast poll interval kicks in.
+    window: Duration,
+}
+
+impl Default for AdaptivePolling {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(5),
+            Duration::from_millis(200),
+            Duration::from_secs(5),
+        )
+    }
+}
+
+impl AdaptivePolling {
+    /// Polls every `slow` mid-track, and every `fast` within `window` of the end of the track.
+    ///
+    /// Zero intervals are bumped up to a millisecond.
+    pub fn new(slow: Duration, fast: Duration, window: Duration) -> Self {
+        Self {
+            slow: slow.max(Duration::from_millis(1)),
+            fast: fast.max(Duration::from_millis(1)),
+            window,
+        }
+    }
+
+    /// Gets the poll interval mid-track.
+    pub fn slow(&self) -> Duration {
+        self.slow
+    }
+
+    /// Gets the poll interval close to the end of the track.
+    pub fn fast(&self) -> Duration {
+        self.fast
+    }
+
+    /// Gets how close to the end of the track the fast poll interval kicks in.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Gets the poll interval for the time left of the track, None if it's not known.
+    pub(crate) fn interval(&self, remaining: Option<Duration>) -> Duration {
+        match remaining {
+            Some(remaining) if remaining <= self.window => self.fast,
+            _ => self.slow,
+        }
+    }
+}
+
+/// When a new position is worth a position event, see [`MprisOptions::with_position_threshold`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PositionThreshold {
+    /// On every poll.
+    #[default]
+    Every,
+
+    /// When the position moved by more than this since the last event.
+    Delta(Duration),
+
+    /// When the position got into another second, for the consumers only showing seconds.
+    WholeSeconds,
+}
+
+impl PositionThreshold {
+    /// Returns `true` if the position is worth an event, after the last sent one.
+    pub(crate) fn passes(&self, last: Option<Duration>, position: Duration) -> bool {
+        let Some(last) = last else {
+            return true;
+        };
+
+        match self {
+            PositionThreshold::Every => true,
+            PositionThreshold::Delta(delta) => last.abs_diff(position) > *delta,
+            PositionThreshold::WholeSeconds => last.as_secs() != position.as_secs(),
+        }
+    }
+}
+
+/// What happens to an event sent out while the event channel is full, see [`EventCapacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drops the oldest queued event to make room.
+    DropOldest,
+
+    /// Drops the event being sent out.
+    DropNewest,
+
+    /// Holds the watch task of the player back until there's room.
+    Block,
+}
+
+/// Bounds the event channel of a [`crate::Mpris`], see [`MprisOptions::with_event_capacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCapacity {
+    /// How many events can be queued up.
+    capacity: usize,
+
+    /// What happens to the events past that.
+    policy: OverflowPolicy,
+}
+
+impl EventCapacity {
+    /// Queues up to `capacity` events, bumped up to one.
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            policy,
+        }
+    }
+
+    /// Gets how many events can be queued up.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Gets what happens to the events past the capacity.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+}
+
+/// Options of a watching [`crate::Mpris`], that can be changed while it runs.
+///
+/// Changes made with [`crate::Mpris::set_options`] or [`crate::Mpris::update_options`]
+/// are picked up by the already running player tasks, no need to watch again.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use mprizzle::{EventFilter, EventKind, Mpris, MprisOptions};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::new().await?;
+///     mpris.watch();
+///
+///     // Later on, e.g. when a user setting changes.
+///     mpris.update_options(|options| {
+///         options
+///             .with_poll_interval(Duration::from_millis(500))
+///             .with_debounce(Duration::from_millis(100))
+///             .with_filter(EventFilter::all().kinds([EventKind::PlayerPosition]))
+///     });
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MprisOptions {
+    /// How often the players get polled for their position.
+    poll_interval: Duration,
+
+    /// How long the properties changed events get held back, to be sent out as one.
+    debounce: Duration,
+
+    /// Which events get sent out.
+    filter: EventFilter,
+
+    /// What tells the tracks apart.
+    track_identity: TrackIdentity,
+
+    /// How long before the end of a track the track ending events get sent out.
+    track_ending: Duration,
+
+    /// Replaces the poll interval with one following the position, if set.
+    adaptive_polling: Option<AdaptivePolling>,
+
+    /// Whether the position gets worked out locally, rather than polled.
+    local_position: bool,
+
+    /// When a new position is worth a position event.
+    position_threshold: PositionThreshold,
+
+    /// Bounds the event channel, if set.
+    event_capacity: Option<EventCapacity>,
+
+    /// How many events a subscription can fall behind.
+    subscription_capacity: usize,
+
+    /// Whether the back-to-back duplicate events of a player get dropped.
+    coalesce_duplicates: bool,
+
+    /// Whether the properties changes of the other interfaces get sent out.
+    other_interfaces: bool,
+
+    /// Whether a player starting to play pauses the others.
+    single_playback: bool,
+
+    /// Whether the watch stops once the last player detached.
+    exit_when_idle: bool,
+}
+
+impl Default for MprisOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(1),
+            debounce: Duration::ZERO,
+            filter: EventFilter::all(),
+            track_identity: TrackIdentity::default(),
+            track_ending: Duration::from_secs(10),
+            adaptive_polling: None,
+            local_position: false,
+            position_threshold: PositionThreshold::default(),
+            event_capacity: None,
+            subscription_capacity: 1024,
+            coalesce_duplicates: false,
+            other_interfaces: false,
+            single_playback: false,
+            exit_when_idle: false,
+        }
+    }
+}
+
+impl MprisOptions {
+    /// Sets how often the players get polled for their position, one second by default.
+    ///
+    /// A zero interval is bumped up to a millisecond.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Sets how long the properties changed events get held back, none by default.
+    ///
+    /// The changes of a player happening within that window are sent out as a single event,
+    /// which helps with players flooding a change per property.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Sets which events get sent out, all of them by default.
+    pub fn with_filter(mut self, filter: EventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets what tells the tracks apart, the `mpris:trackid` by default.
+    pub fn with_track_identity(mut self, track_identity: TrackIdentity) -> Self {
+        self.track_identity = track_identity;
+        self
+    }
+
+    /// Sets how long before the end of a track [`crate::MprisEvent::PlayerTrackEnding`] gets sent out,
+    /// ten seconds by default.
+    ///
+    /// The end is only caught within a poll interval, see [`MprisOptions::with_poll_interval`].
+    pub fn with_track_ending(mut self, track_ending: Duration) -> Self {
+        self.track_ending = track_ending;
+        self
+    }
+
+    /// Sets the poll interval to follow the position instead, off by default.
+    ///
+    /// Polling fast only close to the end of the track catches the track changes early,
+    /// without keeping D-Bus busy the rest of the time. The position events follow the
+    /// poll interval, so they get sent out less often mid-track.
+    pub fn with_adaptive_polling(mut self, adaptive_polling: Option<AdaptivePolling>) -> Self {
+        self.adaptive_polling = adaptive_polling;
+        self
+    }
+
+    /// Sets whether the position gets worked out locally, off by default.
+    ///
+    /// The position events then come from interpolating the position between the seeks
+    /// and the playback status, rate and track changes, only reading it from the player
+    /// after those. This takes the D-Bus traffic of a playing player close to nothing,
+    /// at the cost of trusting the player to signal its changes (see [`crate::PositionTracker`]).
+    pub fn with_local_position(mut self, local_position: bool) -> Self {
+        self.local_position = local_position;
+        self
+    }
+
+    /// Sets when a new position is worth a [`crate::MprisEvent::PlayerPosition`], on every poll by default.
+    pub fn with_position_threshold(mut self, position_threshold: PositionThreshold) -> Self {
+        self.position_threshold = position_threshold;
+        self
+    }
+
+    /// Bounds the event channel of [`crate::Mpris::recv`], unbounded by default.
+    ///
+    /// Keeps a slow consumer from making the memory grow without bound, like during
+    /// a storm of properties changes. Lowering the capacity below the queued events only
+    /// applies to the events sent out next.
+    pub fn with_event_capacity(mut self, event_capacity: Option<EventCapacity>) -> Self {
+        self.event_capacity = event_capacity;
+        self
+    }
+
+    /// Sets how many events a subscription can fall behind before skipping the oldest ones,
+    /// 1024 by default and bumped up to one.
+    ///
+    /// The subscriptions share a single channel, holding that many events at most, see
+    /// [`crate::Mpris::subscribe`]. The channel gets created along with the [`crate::Mpris`],
+    /// so changing it through [`crate::Mpris::update_options`] doesn't resize it.
+    pub fn with_subscription_capacity(mut self, subscription_capacity: usize) -> Self {
+        self.subscription_capacity = subscription_capacity.max(1);
+        self
+    }
+
+    /// Sets whether a player's event gets dropped when it's the same as its previous one, off by default.
+    ///
+    /// The same means the same kind and the same data, so the repeated positions within
+    /// the same second or the repeated property values don't reach the channel.
+    /// The events whose data doesn't tell what changed, like the seeks, always get through.
+    pub fn with_coalesce_duplicates(mut self, coalesce_duplicates: bool) -> Self {
+        self.coalesce_duplicates = coalesce_duplicates;
+        self
+    }
+
+    /// Sets whether the properties changes of the interfaces besides the root and the player one
+    /// get sent out, off by default.
+    ///
+    /// Those are the `TrackList` and `Playlists` ones, or any other a player puts on its path.
+    /// They come as [`crate::MprisEvent::PlayerPropertiesChanged`], told apart with
+    /// [`crate::PropertiesDiff::interface`].
+    pub fn with_other_interfaces(mut self, other_interfaces: bool) -> Self {
+        self.other_interfaces = other_interfaces;
+        self
+    }
+
+    /// Sets whether a player starting to play pauses the other playing ones, off by default.
+    ///
+    /// Each player paused that way comes with a [`crate::MprisEvent::PlayerAutoPaused`].
+    /// This follows the [`crate::MprisEvent::PlayerPlaybackStatusChanged`] events, so it needs
+    /// an [`crate::EventMask`] and a filter keeping them. A player paused a moment ago starting
+    /// to play again doesn't pause the others, so two players can't keep pausing each other.
+    pub fn with_single_playback(mut self, single_playback: bool) -> Self {
+        self.single_playback = single_playback;
+        self
+    }
+
+    /// Sets whether the watch stops once the last player detached, off by default.
+    ///
+    /// The receiver then gets [`crate::MprisError::AllPlayersGone`] as its last event,
+    /// which ends the usual `while let Ok(event) = mpris.recv().await?` loop, so the
+    /// short-lived tools can exit rather than wait for a player forever. Starting without
+    /// any player doesn't stop it, nor do the players going away along with the connection.
+    pub fn with_exit_when_idle(mut self, exit_when_idle: bool) -> Self {
+        self.exit_when_idle = exit_when_idle;
+        self
+    }
+
+    /// Gets the poll interval.
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    /// Gets the debounce window.
+    pub fn debounce(&self) -> Duration {
+        self.debounce
+    }
+
+    /// Gets the event filter.
+    pub fn filter(&self) -> &EventFilter {
+        &self.filter
+    }
+
+    /// Gets what tells the tracks apart.
+    pub fn track_identity(&self) -> &TrackIdentity {
+        &self.track_identity
+    }
+
+    /// Gets how long before the end of a track the track ending events get sent out.
+    pub fn track_ending(&self) -> Duration {
+        self.track_ending
+    }
+
+    /// Gets whether the position gets worked out locally.
+    pub fn local_position(&self) -> bool {
+        self.local_position
+    }
+
+    /// Gets when a new position is worth a position event.
+    pub fn position_threshold(&self) -> PositionThreshold {
+        self.position_threshold
+    }
+
+    /// Gets the bound of the event channel, None if it's unbounded.
+    pub fn event_capacity(&self) -> Option<&EventCapacity> {
+        self.event_capacity.as_ref()
+    }
+
+    /// Gets how many events a subscription can fall behind.
+    pub fn subscription_capacity(&self) -> usize {
+        self.subscription_capacity
+    }
+
+    /// Gets whether the back-to-back duplicate events get dropped.
+    pub fn coalesce_duplicates(&self) -> bool {
+        self.coalesce_duplicates
+    }
+
+    /// Gets whether the properties changes of the other interfaces get sent out.
+    pub fn other_interfaces(&self) -> bool {
+        self.other_interfaces
+    }
+
+    /// Gets whether a player starting to play pauses the others.
+    pub fn single_playback(&self) -> bool {
+        self.single_playback
+    }
+
+    /// Gets whether the watch stops once the last player detached.
+    pub fn exit_when_idle(&self) -> bool {
+        self.exit_when_idle
+    }
+
+    /// Gets the adaptive polling, None if the poll interval is fixed.
+    pub fn adaptive_polling(&self) -> Option<&AdaptivePolling> {
+        self.adaptive_polling.as_ref()
+    }
+}
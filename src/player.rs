@@ -1,65 +1,296 @@
-use std::{collections::HashMap, str::FromStr, sync::Arc, time::Duration};
-
-use futures::StreamExt;
-use tokio::sync::{Mutex, broadcast, mpsc};
-use zbus::{Connection, Proxy, zvariant};
-use zvariant::ObjectPath;
+use std::{
+    collections::HashMap,
+    pin::pin,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
-use crate::{LoopStatus, MprisError, MprisResult, status::PlaybackStatus};
+use futures::{Stream, StreamExt, future};
+use serde::{Serialize, de::DeserializeOwned};
+use tokio::{
+    sync::{Mutex, broadcast, mpsc, watch},
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+use zbus::{
+    Connection,
+    fdo::{self, PropertiesProxy},
+    names::{BusName, InterfaceName},
+    zvariant,
+};
+use zvariant::{DynamicType, ObjectPath, OwnedValue, Type};
+
+use crate::{
+    Capabilities, ComplianceReport, DesktopIcon, EventKind, EventMask, LoopStatus, MetadataDiff,
+    MprisError, MprisOptions, MprisResult, PlayerCredentials, PlayerEvents, PlayerInterfaces,
+    PlayerQuirks, PlayerSnapshot, PlayerState, Playlist, PlaylistOrdering, PositionTracker,
+    PropertiesDiff, TrackId, TrackKey, WatchHandle,
+    capabilities::CAPABILITY_PROPERTIES,
+    changes::PropertiesChanges,
+    desktop::resolve_desktop_icon,
+    lifecycle::TrackLifecycle,
+    signals::{PlayerSignal, SignalSource},
+    stats::{EventSender, Stats},
+    status::PlaybackStatus,
+    track::NO_TRACK,
+};
 
 use super::{
     MprisEvent,
     identity::PlayerIdentity,
     metadata::PlayerMetadata,
-    proxies::{self, create_player_proxy, create_properties_proxy},
+    proxies::{
+        self, DBUS_MPRIS_INTERFACE_NAME, DBUS_MPRIS_INTERFACE_PATH,
+        DBUS_MPRIS_PLAYER_INTERFACE_NAME, DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME, MediaPlayer2Proxy,
+        PlayerProxy, PlaylistsProxy, TrackListProxy, create_properties_proxy,
+    },
 };
 
+/// The properties of the player interface, for getting them one by one.
+const PLAYER_PROPERTIES: [&str; 15] = [
+    "PlaybackStatus",
+    "LoopStatus",
+    "Rate",
+    "Shuffle",
+    "Metadata",
+    "Volume",
+    "Position",
+    "MinimumRate",
+    "MaximumRate",
+    "CanGoNext",
+    "CanGoPrevious",
+    "CanPlay",
+    "CanPause",
+    "CanSeek",
+    "CanControl",
+];
+
+/// Creates the event for a properties change of this kind.
+fn properties_event(
+    kind: EventKind,
+    identity: &PlayerIdentity,
+    diff: PropertiesDiff,
+) -> MprisEvent {
+    match kind {
+        EventKind::PlayerRootPropertiesChanged => {
+            MprisEvent::PlayerRootPropertiesChanged(identity.clone(), diff)
+        }
+        _ => MprisEvent::PlayerPropertiesChanged(identity.clone(), diff),
+    }
+}
+
+/// Changes the period of the ticker, if there's one and it's not the current one already.
+///
+/// The next tick comes a period later, rather than right away.
+fn adapt_ticker(ticker: &mut Option<tokio::time::Interval>, period: Duration) {
+    if let Some(ticker) = ticker
+        && ticker.period() != period
+    {
+        *ticker = tokio::time::interval_at(Instant::now() + period, period);
+    }
+}
+
+/// Waits for the next item of the signal stream, forever if it never got subscribed to.
+async fn next_or_pending<S: Stream + Unpin>(stream: &mut Option<S>) -> Option<S::Item> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => future::pending().await,
+    }
+}
+
+/// Waits for the next tick of the ticker, forever if there's none.
+async fn tick_or_pending(ticker: &mut Option<tokio::time::Interval>) -> Instant {
+    match ticker {
+        Some(ticker) => ticker.tick().await,
+        None => future::pending().await,
+    }
+}
+
+/// Records the new playback status, making the event if it's not the last known one.
+fn playback_status_event(
+    identity: &PlayerIdentity,
+    last_status: &mut Option<PlaybackStatus>,
+    playback_status: PlaybackStatus,
+) -> Option<MprisEvent> {
+    let old = last_status.replace(playback_status.clone())?;
+
+    (old != playback_status)
+        .then(|| MprisEvent::PlayerPlaybackStatusChanged(identity.clone(), old, playback_status))
+}
+
+/// Turns the metadata got from the player proxy into [`PlayerMetadata`].
+pub(crate) fn owned_metadata(metadata: HashMap<String, OwnedValue>) -> PlayerMetadata<'static> {
+    PlayerMetadata::new(
+        metadata
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect(),
+    )
+}
+
+/// Deserializes the values of a `PropertiesChanged` body, along with the invalidated names.
+fn changed_values(body: &zbus::message::Body) -> (HashMap<String, OwnedValue>, Vec<String>) {
+    body.deserialize::<(&str, HashMap<String, OwnedValue>, Vec<String>)>()
+        .map(|(_, changed, invalidated)| (changed, invalidated))
+        .unwrap_or_default()
+}
+
+/// Returns `true` if the error means the player doesn't implement the called interface.
+fn is_unimplemented(err: &zbus::Error) -> bool {
+    match err {
+        zbus::Error::MethodError(name, _, _) => matches!(
+            name.as_str(),
+            "org.freedesktop.DBus.Error.UnknownMethod"
+                | "org.freedesktop.DBus.Error.UnknownInterface"
+                | "org.freedesktop.DBus.Error.UnknownObject"
+        ),
+        zbus::Error::FDO(err) => matches!(
+            **err,
+            fdo::Error::UnknownMethod(_)
+                | fdo::Error::UnknownInterface(_)
+                | fdo::Error::UnknownObject(_)
+        ),
+        _ => false,
+    }
+}
+
+/// How many position ticks go by before the position is read from the player again.
+const POSITION_SYNC_TICKS: u32 = 5;
+
+/// How long a newly attached player has to give its state, before getting announced without it.
+const SNAPSHOT_TIMEOUT: Duration = Duration::from_millis(250);
+
 /// Represents errors that can occur in MPRIS Player operations.
-#[derive(Debug, thiserror::Error)]
+///
+/// Every error carries the bus name of the player it came from first,
+/// so the errors of different players can be told apart.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum PlayerError {
-    #[error("Failed to get player prop: {0}: {1}")]
-    FailedToGetProp(String, String),
+    #[error("{0}: Failed to get player prop: {1}: {2}")]
+    FailedToGetProp(String, String, String),
+
+    #[error("{0}: Failed to set player prop: {1}: {2}")]
+    FailedToSetProp(String, String, String),
 
-    #[error("Failed to set player prop: {0}: {1}")]
-    FailedToSetProp(String, String),
+    #[error("{0}: Failed to call {1} mpris function: {2}")]
+    FailedToCallFn(String, String, String),
 
-    #[error("Failed to call {0} mpris function: {1}")]
-    FailedToCallFn(String, String),
+    #[error("{0}: Unsupported uri: {1}: {2}")]
+    UnsupportedUri(String, String, String),
 
-    #[error("{0}")]
-    Other(String),
+    #[error("{0}: The player doesn't implement {1}")]
+    UnsupportedInterface(String, String),
+
+    #[error("{0}: No track is loaded")]
+    NoTrack(String),
+
+    /// The player doesn't allow the action right now, its `capability` property is false.
+    #[error("{bus}: Cannot {action} when {capability} is false")]
+    CapabilityDenied {
+        bus: String,
+        capability: String,
+        action: String,
+    },
+
+    #[error("{0}: {1}")]
+    Other(String, String),
 }
 
 impl PlayerError {
-    pub fn failed_to_get_prop<P, E>(prop: P, err: E) -> MprisError
+    pub fn failed_to_get_prop<P, E>(bus: &str, prop: P, err: E) -> MprisError
     where
         P: Into<String>,
         E: Into<String>,
     {
-        MprisError::PlayerErr(PlayerError::FailedToGetProp(prop.into(), err.into()))
+        MprisError::PlayerErr(PlayerError::FailedToGetProp(
+            bus.into(),
+            prop.into(),
+            err.into(),
+        ))
     }
 
-    pub fn failed_to_set_prop<P, E>(prop: P, err: E) -> MprisError
+    pub fn failed_to_set_prop<P, E>(bus: &str, prop: P, err: E) -> MprisError
     where
         P: Into<String>,
         E: Into<String>,
     {
-        MprisError::PlayerErr(PlayerError::FailedToSetProp(prop.into(), err.into()))
+        MprisError::PlayerErr(PlayerError::FailedToSetProp(
+            bus.into(),
+            prop.into(),
+            err.into(),
+        ))
     }
 
-    pub fn failed_to_call_fn<F, E>(name: F, err: E) -> MprisError
+    pub fn failed_to_call_fn<F, E>(bus: &str, name: F, err: E) -> MprisError
     where
         F: Into<String>,
         E: Into<String>,
     {
-        MprisError::PlayerErr(PlayerError::FailedToCallFn(name.into(), err.into()))
+        MprisError::PlayerErr(PlayerError::FailedToCallFn(
+            bus.into(),
+            name.into(),
+            err.into(),
+        ))
+    }
+
+    pub fn unsupported_uri<U, E>(bus: &str, uri: U, err: E) -> MprisError
+    where
+        U: Into<String>,
+        E: Into<String>,
+    {
+        MprisError::PlayerErr(PlayerError::UnsupportedUri(
+            bus.into(),
+            uri.into(),
+            err.into(),
+        ))
+    }
+
+    pub fn unsupported_interface<I>(bus: &str, interface: I) -> MprisError
+    where
+        I: Into<String>,
+    {
+        MprisError::PlayerErr(PlayerError::UnsupportedInterface(
+            bus.into(),
+            interface.into(),
+        ))
+    }
+
+    pub fn capability_denied<C, A>(bus: &str, capability: C, action: A) -> MprisError
+    where
+        C: Into<String>,
+        A: Into<String>,
+    {
+        MprisError::PlayerErr(PlayerError::CapabilityDenied {
+            bus: bus.into(),
+            capability: capability.into(),
+            action: action.into(),
+        })
+    }
+
+    pub fn no_track(bus: &str) -> MprisError {
+        MprisError::PlayerErr(PlayerError::NoTrack(bus.into()))
     }
 
-    pub fn other<E>(err: E) -> MprisError
+    pub fn other<E>(bus: &str, err: E) -> MprisError
     where
         E: Into<String>,
     {
-        MprisError::PlayerErr(PlayerError::Other(err.into()))
+        MprisError::PlayerErr(PlayerError::Other(bus.into(), err.into()))
+    }
+
+    /// Gets the bus name of the player the error came from.
+    pub fn bus(&self) -> &str {
+        match self {
+            PlayerError::FailedToGetProp(bus, _, _)
+            | PlayerError::FailedToSetProp(bus, _, _)
+            | PlayerError::FailedToCallFn(bus, _, _)
+            | PlayerError::UnsupportedUri(bus, _, _)
+            | PlayerError::UnsupportedInterface(bus, _)
+            | PlayerError::NoTrack(bus)
+            | PlayerError::CapabilityDenied { bus, .. }
+            | PlayerError::Other(bus, _) => bus,
+        }
     }
 }
 
@@ -68,6 +299,9 @@ impl PlayerError {
 /// This struct provides an interface to control and retrieve information from an MPRIS-compatible media player.
 /// It uses a D-Bus proxy to communicate with the player and manage playback.
 ///
+/// Cloning is cheap, the clones share the proxies and what was learned about the player,
+/// so the event loop, a UI task and a hotkey handler can each hold onto their own.
+///
 /// # Example
 ///
 /// ```no_run
@@ -79,26 +313,53 @@ impl PlayerError {
 ///
 ///     // Ideally you should never create your own player and just use the one from `mpris`
 ///     // but you can just create a player yourself.
-///     let spotify = Player::new(mpris.connection(), PlayerIdentity::new("org.mpris.MediaPlayer2.spotify".into())).await?;
+///     let spotify = MprisPlayer::new(mpris.connection(), PlayerIdentity::new("org.mpris.MediaPlayer2.spotify".into())?).await?;
 ///
 ///     let metadata = spotify.metadata().await?;
 ///
 ///     let title = metadata.title()?.unwrap_or("No Title".into());
-///     println("Current song: {title}");
+///     println!("Current song: {title}");
 ///
 ///     Ok(())
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MprisPlayer {
+    /// Shared by the clones.
+    inner: Arc<PlayerInner>,
+}
+
+#[derive(Debug, Clone)]
+struct PlayerInner {
     /// A shared D-Bus connection.
     connection: Arc<Mutex<Connection>>,
 
+    /// Root proxy.
+    root_proxy: MediaPlayer2Proxy<'static>,
+
     /// Player proxy.
-    player_proxy: Proxy<'static>,
+    player_proxy: PlayerProxy<'static>,
+
+    /// Properties proxy.
+    properties_proxy: PropertiesProxy<'static>,
+
+    /// Track list proxy, the player might not implement it (see [`MprisPlayer::has_track_list`]).
+    track_list_proxy: TrackListProxy<'static>,
+
+    /// Playlists proxy, the player might not implement it either.
+    playlists_proxy: PlaylistsProxy<'static>,
+
+    /// The misbehaviors found so far.
+    quirks: Arc<std::sync::Mutex<PlayerQuirks>>,
+
+    /// The cached `Identity`, cleared when the player changes it.
+    display_name: Arc<std::sync::Mutex<Option<String>>>,
 
     /// The identity of this player.
     identity: PlayerIdentity,
+
+    /// Runtime counters, shared with the [`crate::Mpris`] that created the player.
+    stats: Stats,
 }
 
 impl MprisPlayer {
@@ -106,78 +367,211 @@ impl MprisPlayer {
         shared_connection: Arc<Mutex<Connection>>,
         identity: PlayerIdentity,
     ) -> MprisResult<Self> {
+        let shared_conn = Arc::clone(&shared_connection);
+        let root_proxy = proxies::create_root_proxy(shared_conn, identity.bus()).await?;
+
         let shared_conn = Arc::clone(&shared_connection);
         let player_proxy = proxies::create_player_proxy(shared_conn, identity.bus()).await?;
 
-        Ok(Self {
+        let shared_conn = Arc::clone(&shared_connection);
+        let properties_proxy = create_properties_proxy(shared_conn, identity.bus()).await?;
+
+        let shared_conn = Arc::clone(&shared_connection);
+        let track_list_proxy =
+            proxies::create_track_list_proxy(shared_conn, identity.bus()).await?;
+
+        let shared_conn = Arc::clone(&shared_connection);
+        let playlists_proxy = proxies::create_playlists_proxy(shared_conn, identity.bus()).await?;
+
+        let inner = PlayerInner {
             connection: shared_connection,
+            root_proxy,
             player_proxy,
+            properties_proxy,
+            track_list_proxy,
+            playlists_proxy,
+            quirks: Arc::default(),
+            display_name: Arc::default(),
             identity,
+            stats: Stats::default(),
+        };
+
+        Ok(Self {
+            inner: Arc::new(inner),
         })
     }
 
-    /// Start watching for player events.
+    /// Counts the activity of this player in the given stats.
+    pub(crate) fn with_stats(mut self, stats: Stats) -> Self {
+        // Only copies the state if the player got cloned already.
+        Arc::make_mut(&mut self.inner).stats = stats;
+        self
+    }
+
+    /// Watches this player on its own, with the default options.
+    ///
+    /// Each call gets its own receiver and watch task, scoped to this player and
+    /// independent of [`crate::Mpris::watch`], see [`PlayerEvents`].
+    pub fn events(&self) -> PlayerEvents {
+        self.events_with(MprisOptions::default())
+    }
+
+    /// Watches this player on its own, with these options.
+    pub fn events_with(&self, options: MprisOptions) -> PlayerEvents {
+        PlayerEvents::new(self, options)
+    }
+
+    /// Start watching for player events, following the changes made to the options.
+    ///
+    /// Only the signals and the polling needed by the events in the mask get set up.
+    /// The task stops once the sender gets closed, the token gets cancelled,
+    /// or the handle gets dropped, see [`WatchHandle`].
+    #[must_use = "the watch task stops once its handle gets dropped"]
     pub fn watch(
         &self,
         event_sender: mpsc::UnboundedSender<MprisResult<MprisEvent>>,
-        mut close_rx: broadcast::Receiver<String>,
-    ) {
+        cancel: CancellationToken,
+        options_rx: watch::Receiver<MprisOptions>,
+        mask: EventMask,
+    ) -> WatchHandle {
+        let event_sender = EventSender::new(event_sender, self.inner.stats.clone())
+            .with_options(options_rx.clone());
+        let task = self.watch_task(
+            event_sender.clone(),
+            cancel,
+            options_rx,
+            mask,
+            SignalSource::Own,
+            false,
+        );
+
+        WatchHandle::spawn(event_sender, task)
+    }
+
+    /// Creates the task watching for player events, sending them out through the sender
+    /// of a [`crate::Mpris`], to be spawned by a [`WatchHandle`].
+    ///
+    /// Sends out the PlayerAttached event of the player first when `announce` is set.
+    pub(crate) fn watch_task(
+        &self,
+        event_sender: EventSender,
+        cancel: CancellationToken,
+        mut options_rx: watch::Receiver<MprisOptions>,
+        mask: EventMask,
+        signal_source: SignalSource,
+        announce: bool,
+    ) -> impl Future<Output = MprisResult<()>> + Send + 'static {
         let shared_connection = self.connection();
         let identity = self.identity().clone();
-
-        tokio::spawn(async move {
-            // Creates a properties proxy.
+        let stats = self.inner.stats.clone();
+        let display_name = Arc::clone(&self.inner.display_name);
+        let properties_proxy = self.inner.properties_proxy.clone();
+        let player_proxy = self.inner.player_proxy.clone();
+        let attached = announce.then(|| self.clone());
+
+        // Whether the events of a kind get sent out.
+        let wants = move |options: &MprisOptions, kind: EventKind| {
+            mask.contains_kind(kind) && options.filter().matches_kind(kind)
+        };
+
+        async move {
+            // Gets the signals of the player, unless the mask leaves out their events.
             let shared_conn = Arc::clone(&shared_connection);
-            let properties_proxy = match create_properties_proxy(shared_conn, identity.bus()).await
-            {
-                Ok(properties_proxy) => properties_proxy,
-                Err(err) => {
-                    event_sender.send(Err(err.into())).unwrap();
-                    return;
-                }
-            };
+            let mut signals = signal_source
+                .signals(shared_conn, identity.bus(), mask)
+                .await?;
+
+            let mut options = options_rx.borrow_and_update().clone();
+
+            // Create a ticker that tick each poll interval to tick me, unless there's nothing to poll for.
+            let mut tickler = mask
+                .needs_ticker()
+                .then(|| tokio::time::interval(options.poll_interval()));
+
+            // The properties changed events held back by the debounce, and until when.
+            let mut pending: Vec<(EventKind, PropertiesDiff, SystemTime)> = Vec::new();
+            let mut deadline: Option<Instant> = None;
+
+            // Interpolates the position between the reads, None until it gets read from the player.
+            let mut tracker: Option<PositionTracker> = None;
+            let mut last_position: Option<Duration> = None;
+
+            // Nothing follows the player's state, when the mask only lets through the plain signals.
+            let followed = mask.needs_properties() || mask.needs_ticker();
+
+            // The last known metadata, playback status and capabilities, for telling what changed about them.
+            let mut metadata = None;
+            let mut last_status = None;
+            let mut capabilities = None;
+
+            // Read with a single `GetAll`, along with the snapshot of the PlayerAttached event.
+            let attached = attached.filter(|_| wants(&options, EventKind::PlayerAttached));
+            if followed || attached.is_some() || mask.contains(EventMask::CAPABILITIES) {
+                stats.record_call(&identity);
+                let interface =
+                    InterfaceName::from_static_str_unchecked(DBUS_MPRIS_PLAYER_INTERFACE_NAME);
+                let mut get_all = pin!(async {
+                    properties_proxy
+                        .get_all(interface)
+                        .await
+                        .ok()
+                        .map(PlayerState::new)
+                });
+
+                // A player that doesn't answer in time gets announced with an empty snapshot,
+                // rather than holding up the event, its state still gets waited for.
+                let mut state = None;
+                if let Some(player) = attached {
+                    let answered = tokio::time::timeout(SNAPSHOT_TIMEOUT, &mut get_all).await;
+                    let snapshot = match &answered {
+                        Ok(Some(state)) => PlayerSnapshot::from_state(state),
+                        _ => PlayerSnapshot::default(),
+                    };
+
+                    // The player went away in the meantime, its PlayerDetached event might be out already.
+                    if cancel.is_cancelled() {
+                        return Ok(());
+                    }
 
-            // Creates a player proxy.
-            let shared_conn = Arc::clone(&shared_connection);
-            let player_proxy = match create_player_proxy(shared_conn, identity.bus()).await {
-                Ok(player_proxy) => player_proxy,
-                Err(err) => {
-                    event_sender.send(Err(err.into())).unwrap();
-                    return;
-                }
-            };
-
-            // Creates a PropertiesChanged signal stream.
-            let mut prop_changed_stream =
-                match properties_proxy.receive_signal("PropertiesChanged").await {
-                    Ok(properties_changed) => properties_changed,
-                    Err(err) => {
-                        event_sender
-                            .send(Err(MprisError::Other(format!(
-                                "Failed to create a signal stream for PropertiesChanged: {err}"
-                            ))))
-                            .unwrap();
-
-                        return;
+                    if event_sender
+                        .send(Ok(MprisEvent::PlayerAttached(player, snapshot)))
+                        .await
+                        .is_err()
+                    {
+                        return Ok(());
                     }
+
+                    state = answered.ok();
+                }
+
+                let state = match state {
+                    Some(state) => state,
+                    None => get_all.await,
                 };
 
-            // Creates a Seeked signal stream.
-            let mut seeked_stream = match player_proxy.receive_signal("Seeked").await {
-                Ok(seeked_stream) => seeked_stream,
-                Err(err) => {
-                    event_sender
-                        .send(Err(MprisError::Other(format!(
-                            "Failed to create a signal stream for Seeked: {err}"
-                        ))))
-                        .unwrap();
-
-                    return;
+                if let Some(state) = state {
+                    if followed {
+                        metadata = state.metadata().cloned();
+                        last_status = state.playback_status().cloned();
+                    }
+
+                    if mask.contains(EventMask::CAPABILITIES) {
+                        capabilities = Some(state.capabilities());
+                    }
                 }
-            };
+            }
+
+            let mut track: Option<TrackKey> = metadata
+                .as_ref()
+                .and_then(|metadata| options.track_identity().key(metadata));
 
-            // Create a ticker that tick each seconds to tick me.
-            let mut tickler = tokio::time::interval(Duration::from_secs(1));
+            // Follows the current track for the started and finished events.
+            let mut lifecycle = TrackLifecycle::default();
+
+            // A hash of the last known value of each property, to tell the no-op changes apart.
+            // Kept apart for each interface, as they can have properties of the same name.
+            let mut fingerprints: HashMap<String, HashMap<String, u64>> = HashMap::new();
+            let mut ticks_since_sync = 0;
 
             loop {
                 tokio::select! {
@@ -189,278 +583,922 @@ impl MprisPlayer {
                     // Break out of the loop if the event channel has been closed.
                     _ = event_sender.closed() => break,
 
-                    // Break out of the loop if the close channel event bus matches the identity.
-                    close_res = close_rx.recv() => {
-                        let bus = match close_res {
-                            Ok(bus) => bus,
-                            Err(err) => {
-                                event_sender.send(Err(MprisError::Other(format!("Failed to receive close event: {err}")))).unwrap();
-                                break;
-                            }
-                        };
+                    // Break out of the loop once the player got detached.
+                    _ = cancel.cancelled() => break,
+
+                    // Apply the changed options.
+                    Ok(()) = options_rx.changed() => {
+                        let changed = options_rx.borrow_and_update().clone();
 
-                        // Break if it checks out.
-                        if identity.matches_bus_prefix(&bus) {
-                            break
+                        // The adaptive polling sets the interval on its own, on the next tick.
+                        if let Some(ticker) = &mut tickler
+                            && changed.adaptive_polling().is_none()
+                            && changed.poll_interval() != ticker.period()
+                        {
+                            *ticker = tokio::time::interval(changed.poll_interval());
                         }
+
+                        options = changed;
                     },
 
-                    // Receive PropertiesChanged signal.
-                    Some(_) = prop_changed_stream.next() => {
-                        // Send out PlayerPropertiesChanged event.
-                        event_sender.send(Ok(MprisEvent::PlayerPropertiesChanged(identity.clone()))).unwrap();
+                    // Send out the held back properties changed events.
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                        deadline = None;
+
+                        for (kind, diff, received_at) in pending.drain(..) {
+                            if event_sender.send_at(Ok(properties_event(kind, &identity, diff)), received_at).await.is_err() {
+                                return Ok(());
+                            }
+                        }
                     },
 
-                    // Receive Seeked signal.
-                    Some(_) = seeked_stream.next() => {
-                        // Send out PlayerSeeked event.
-                        event_sender.send(Ok(MprisEvent::PlayerSeeked(identity.clone()))).unwrap();
+                    // Receive the signals of the player.
+                    Some(signal) = next_or_pending(&mut signals) => {
+                        stats.record_signal(&identity);
+
+                        match signal {
+                            // Receive PropertiesChanged signal.
+                            PlayerSignal::PropertiesChanged(signal) => {
+                                // Only looks at the names of the changed properties, borrowed from the body.
+                                let body = signal.message().body();
+                                let Ok(mut changes) = body.deserialize_unchecked::<PropertiesChanges>() else {
+                                    continue;
+                                };
+
+                                // Swallow the changes that change nothing, some players resend the same values.
+                                let known = fingerprints.entry(changes.interface().to_string()).or_default();
+                                let unchanged = changes.invalidated().is_empty()
+                                    && changes.fingerprints().all(|(property, fingerprint)| {
+                                        known.get(property) == Some(&fingerprint)
+                                    });
+
+                                for (property, fingerprint) in changes.fingerprints() {
+                                    known.insert(property.to_string(), fingerprint);
+                                }
+
+                                for property in changes.invalidated() {
+                                    known.remove(*property);
+                                }
+
+                                if unchanged {
+                                    continue;
+                                }
+
+                                // Only the player interface drives the track, status and position.
+                                let player_changes = changes.interface() == DBUS_MPRIS_PLAYER_INTERFACE_NAME;
+
+                                if player_changes && changes.contains("Metadata") {
+                                    let mut new_metadata = changes.take_metadata().map(|metadata| metadata.to_owned_metadata());
+
+                                    // The invalidated metadata has to be fetched for the diff and the track events.
+                                    let wanted = [
+                                        EventKind::PlayerMetadataChanged,
+                                        EventKind::PlayerTrackChanged,
+                                        EventKind::PlayerTrackStarted,
+                                        EventKind::PlayerTrackFinished,
+                                        EventKind::PlayerTrackScrobblable,
+                                        EventKind::PlayerTrackEnding,
+                                    ]
+                                    .into_iter()
+                                    .any(|kind| wants(&options, kind));
+
+                                    if new_metadata.is_none() && wanted {
+                                        stats.record_call(&identity);
+                                        new_metadata = player_proxy.metadata().await.ok().map(owned_metadata);
+                                    }
+
+                                    // A track change starts the position over.
+                                    let key = new_metadata.as_ref().and_then(|metadata| options.track_identity().key(metadata));
+
+                                    // Without the new metadata, it's safer to assume the track changed.
+                                    if key.is_none() || key != track {
+                                        tracker = None;
+                                    }
+
+                                    if key.is_some() && key != track {
+                                        // Send out PlayerTrackFinished event, for the track that got skipped or ran out.
+                                        if let Some(finished) = lifecycle.track_changed()
+                                            && wants(&options, EventKind::PlayerTrackFinished)
+                                            && event_sender.send(Ok(MprisEvent::PlayerTrackFinished(identity.clone(), finished))).await.is_err()
+                                        {
+                                            return Ok(());
+                                        }
+
+                                        // Send out PlayerTrackChanged event, only for another track.
+                                        if let Some(new_metadata) = &new_metadata
+                                            && wants(&options, EventKind::PlayerTrackChanged)
+                                            && event_sender.send(Ok(MprisEvent::PlayerTrackChanged(identity.clone(), new_metadata.to_owned_metadata()))).await.is_err()
+                                        {
+                                            return Ok(());
+                                        }
+                                    }
+
+                                    track = key;
+
+                                    // Send out PlayerMetadataChanged event, if a field actually changed.
+                                    if let Some(new_metadata) = new_metadata {
+                                        let old_metadata = metadata.replace(new_metadata.to_owned_metadata());
+                                        let diff = MetadataDiff::new(old_metadata, new_metadata);
+
+                                        if !diff.is_empty() && wants(&options, EventKind::PlayerMetadataChanged)
+                                            && event_sender.send(Ok(MprisEvent::PlayerMetadataChanged(identity.clone(), diff))).await.is_err()
+                                        {
+                                            return Ok(());
+                                        }
+                                    }
+                                }
+
+                                // Send out PlayerPlaybackStatusChanged event, the invalidated status has to be fetched.
+                                let lifecycle_wanted = wants(&options, EventKind::PlayerTrackStarted)
+                                    || wants(&options, EventKind::PlayerTrackFinished);
+
+                                if player_changes
+                                    && changes.contains("PlaybackStatus")
+                                    && (lifecycle_wanted || options.local_position() || wants(&options, EventKind::PlayerPlaybackStatusChanged))
+                                {
+                                    let mut playback_status = changes.playback_status();
+
+                                    if changes.invalidated().contains(&"PlaybackStatus") {
+                                        stats.record_call(&identity);
+                                        playback_status = player_proxy
+                                            .playback_status()
+                                            .await
+                                            .ok()
+                                            .and_then(|playback_status| PlaybackStatus::from_str(&playback_status).ok());
+                                    }
+
+                                    if let Some(playback_status) = playback_status
+                                        && let Some(event) = playback_status_event(&identity, &mut last_status, playback_status)
+                                        && wants(&options, EventKind::PlayerPlaybackStatusChanged)
+                                        && event_sender.send(Ok(event)).await.is_err()
+                                    {
+                                        return Ok(());
+                                    }
+                                }
+
+                                // Send out PlayerTrackStarted event, for a new track or the first play of the current one.
+                                if last_status == Some(PlaybackStatus::Playing)
+                                    && let Some(metadata) = &metadata
+                                    && let Some(started) = lifecycle.playing(metadata)
+                                    && wants(&options, EventKind::PlayerTrackStarted)
+                                    && event_sender.send(Ok(MprisEvent::PlayerTrackStarted(identity.clone(), started))).await.is_err()
+                                {
+                                    return Ok(());
+                                }
+
+                                // Send out PlayerCapabilitiesChanged event, the invalidated ones have to be fetched.
+                                if player_changes
+                                    && CAPABILITY_PROPERTIES.iter().any(|property| changes.contains(property))
+                                    && wants(&options, EventKind::PlayerCapabilitiesChanged)
+                                {
+                                    let (mut changed, invalidated) = changed_values(&body);
+                                    let interface = InterfaceName::from_static_str_unchecked(DBUS_MPRIS_PLAYER_INTERFACE_NAME);
+
+                                    for property in invalidated.iter().filter(|property| CAPABILITY_PROPERTIES.contains(&property.as_str())) {
+                                        stats.record_call(&identity);
+                                        if let Ok(value) = properties_proxy.get(interface.clone(), property).await {
+                                            changed.insert(property.clone(), value);
+                                        }
+                                    }
+
+                                    let mut updated = capabilities.unwrap_or_default();
+                                    updated.update(&changed);
+
+                                    if capabilities.replace(updated) != Some(updated)
+                                        && event_sender.send(Ok(MprisEvent::PlayerCapabilitiesChanged(identity.clone(), updated))).await.is_err()
+                                    {
+                                        return Ok(());
+                                    }
+                                }
+
+                                // The local position moves on at the new rate, an invalidated one has to be read again.
+                                if player_changes && changes.contains("Rate") && options.local_position() {
+                                    let rate = changed_values(&body).0.get("Rate").and_then(|rate| f64::try_from(rate).ok());
+
+                                    match (&mut tracker, rate) {
+                                        (Some(tracker), Some(rate)) => tracker.set_rate(rate),
+                                        _ => tracker = None,
+                                    }
+                                }
+
+                                // Every interface lives on the same path, the others only get through when opted into.
+                                let kind = match changes.interface() {
+                                    DBUS_MPRIS_PLAYER_INTERFACE_NAME => EventKind::PlayerPropertiesChanged,
+                                    DBUS_MPRIS_INTERFACE_NAME => EventKind::PlayerRootPropertiesChanged,
+                                    _ if options.other_interfaces() => EventKind::PlayerPropertiesChanged,
+                                    _ => continue,
+                                };
+
+                                // The cached display name is stale, whether or not the event gets through the filter.
+                                if kind == EventKind::PlayerRootPropertiesChanged && changes.contains("Identity") {
+                                    *display_name.lock().unwrap_or_else(|err| err.into_inner()) = None;
+                                }
+
+                                // Skip the changes the filter doesn't care about.
+                                if !wants(&options, kind) || !options.filter().matches_properties(changes.properties()) {
+                                    continue;
+                                }
+
+                                // Only now the values get deserialized, for the changes that made it this far.
+                                let (changed, invalidated) = changed_values(&body);
+
+                                // Refetches the invalidated properties, their values weren't sent.
+                                let mut diff = PropertiesDiff::new(changes.interface(), changed, invalidated);
+                                let interface = InterfaceName::from_str_unchecked(changes.interface());
+
+                                for &property in changes.invalidated() {
+                                    stats.record_call(&identity);
+                                    match properties_proxy.get(interface.clone(), property).await {
+                                        Ok(value) => diff.insert(property, value),
+                                        Err(err) => {
+                                            if event_sender.send(Err(PlayerError::failed_to_get_prop(identity.bus(), property, err.to_string()))).await.is_err() {
+                                                return Ok(());
+                                            }
+                                        }
+                                    }
+                                }
+
+                                // Send out PlayerPropertiesChanged or PlayerRootPropertiesChanged event.
+                                if options.debounce().is_zero() {
+                                    if event_sender.send(Ok(properties_event(kind, &identity, diff))).await.is_err() {
+                                        return Ok(());
+                                    }
+                                    continue;
+                                }
+
+                                // Or hold it back until the debounce window is over.
+                                match pending.iter_mut().find(|(pending_kind, pending_diff, _)| *pending_kind == kind && pending_diff.interface() == diff.interface()) {
+                                    Some((_, pending_diff, _)) => pending_diff.merge(diff),
+                                    None => pending.push((kind, diff, SystemTime::now())),
+                                }
+
+                                deadline.get_or_insert_with(|| Instant::now() + options.debounce());
+                            }
+
+                            // Receive Seeked signal.
+                            PlayerSignal::Seeked(signal) => {
+                                // A seek is allowed to move the position backwards.
+                                match (&mut tracker, signal.args()) {
+                                    (Some(tracker), Ok(args)) => tracker.seeked(Duration::from_micros(*args.position() as u64)),
+                                    (_, Err(_)) => tracker = None,
+                                    (None, Ok(_)) => {}
+                                }
+
+                                // Send out PlayerSeeked event.
+                                if wants(&options, EventKind::PlayerSeeked)
+                                    && event_sender.send(Ok(MprisEvent::PlayerSeeked(identity.clone()))).await.is_err()
+                                {
+                                    return Ok(());
+                                }
+                            }
+
+                            // Receive PlaylistChanged signal.
+                            PlayerSignal::PlaylistChanged(signal) => {
+                                let Ok(args) = signal.args() else {
+                                    continue;
+                                };
+
+                                // Send out PlayerPlaylistChanged event.
+                                if wants(&options, EventKind::PlayerPlaylistChanged) {
+                                    let playlist = Playlist::from(args.playlist().clone());
+                                    if event_sender.send(Ok(MprisEvent::PlayerPlaylistChanged(identity.clone(), playlist))).await.is_err() {
+                                        return Ok(());
+                                    }
+                                }
+                            }
+                        }
                     },
 
                     // Tick that tickler!
-                    _ = tickler.tick() => {
-                        // Gets the player playback status from D-Bus.
-                        let playback_status: String = match player_proxy.get_property("PlaybackStatus").await {
-                            Ok(playback_status) => playback_status,
-                            Err(err) => {
-                                event_sender.send(Err(PlayerError::failed_to_get_prop("PlaybackStatus", err.to_string()))).unwrap();
-                                return;
-                            }
-                        };
+                    _ = tick_or_pending(&mut tickler) => {
+                        // The local position goes by the signaled playback status.
+                        let signaled_status = last_status.clone().filter(|_| options.local_position());
+
+                        let playback_status = match signaled_status {
+                            Some(playback_status) => playback_status,
+                            None => {
+                                // Gets the player playback status from D-Bus.
+                                stats.record_call(&identity);
+                                let playback_status = match player_proxy.playback_status().await {
+                                    Ok(playback_status) => playback_status,
+                                    Err(err) => {
+                                        return Err(PlayerError::failed_to_get_prop(identity.bus(), "PlaybackStatus", err.to_string()));
+                                    }
+                                };
+
+                                // Converts the playback status into PlaybackStatus type.
+                                let playback_status = match PlaybackStatus::from_str(&playback_status) {
+                                    Ok(playback_status) => playback_status,
+                                    Err(err) => {
+                                        return Err(PlayerError::other(identity.bus(), format!("Failed to parse playback status: {err}")));
+                                    }
+                                };
+
+                                // Catches the status changes of the players that don't signal them.
+                                if let Some(event) = playback_status_event(&identity, &mut last_status, playback_status.clone())
+                                    && wants(&options, EventKind::PlayerPlaybackStatusChanged)
+                                    && event_sender.send(Ok(event)).await.is_err()
+                                {
+                                    return Ok(());
+                                }
 
-                        // Converts the playback status into PlaybackStatus type.
-                        let playback_status = match PlaybackStatus::from_str(&playback_status) {
-                            Ok(playback_status) => playback_status,
-                            Err(err) => {
-                                event_sender.send(Err(MprisError::Other(format!("Failed to parse playback status: {err}")))).unwrap();
-                                return;
+                                playback_status
                             }
                         };
 
                         // Only send out the PlayerPosition event if the playback is Playing.
-                        if playback_status == PlaybackStatus::Playing {
+                        if playback_status != PlaybackStatus::Playing {
+                            if let Some(tracker) = &mut tracker {
+                                tracker.set_status(playback_status);
+                            }
+
+                            lifecycle.paused();
+
+                            if let Some(adaptive_polling) = options.adaptive_polling() {
+                                adapt_ticker(&mut tickler, adaptive_polling.interval(None));
+                            }
+
+                            continue;
+                        }
+
+                        // Send out PlayerTrackStarted event, for the players that were already playing.
+                        if let Some(metadata) = &metadata
+                            && let Some(started) = lifecycle.playing(metadata)
+                            && wants(&options, EventKind::PlayerTrackStarted)
+                            && event_sender.send(Ok(MprisEvent::PlayerTrackStarted(identity.clone(), started))).await.is_err()
+                        {
+                            return Ok(());
+                        }
+
+                        // Send out PlayerTrackScrobblable event, once the track played long enough.
+                        if let Some(scrobblable) = lifecycle.tick_playing()
+                            && wants(&options, EventKind::PlayerTrackScrobblable)
+                            && event_sender.send(Ok(MprisEvent::PlayerTrackScrobblable(identity.clone(), scrobblable))).await.is_err()
+                        {
+                            return Ok(());
+                        }
+
+                        // The position is only needed for these.
+                        let position_wanted = options.adaptive_polling().is_some()
+                            || [
+                                EventKind::PlayerPosition,
+                                EventKind::PlayerTrackEnding,
+                                EventKind::PlayerTrackFinished,
+                            ]
+                            .into_iter()
+                            .any(|kind| wants(&options, kind));
+
+                        if !position_wanted {
+                            continue;
+                        }
+
+                        // Resyncs the clock with the player every few ticks.
+                        ticks_since_sync += 1;
+                        let resync = !options.local_position() && ticks_since_sync >= POSITION_SYNC_TICKS;
+                        // The position gets read again once the player plays again.
+                        let playing = tracker.as_ref().is_some_and(|tracker| *tracker.status() == PlaybackStatus::Playing);
+                        if !playing || resync {
+                            ticks_since_sync = 0;
+
                             // Gets the player position from the D-Bus.
-                            let position: i64 = match player_proxy.get_property("Position").await {
+                            stats.record_call(&identity);
+                            let position = match player_proxy.position().await {
                                 Ok(position) => position,
                                 Err(err) => {
-                                event_sender.send(Err(PlayerError::failed_to_get_prop("Position", err.to_string()))).unwrap();
-                                    return;
+                                    return Err(PlayerError::failed_to_get_prop(identity.bus(), "Position", err.to_string()));
                                 }
                             };
 
-                            // Converts the player position into Duration type.
+                            // Not every player has a Rate, those play at the normal one.
+                            stats.record_call(&identity);
+                            let rate = player_proxy.rate().await.unwrap_or(1.0);
+
                             let position = Duration::from_micros(position as u64);
+                            match &mut tracker {
+                                Some(tracker) => {
+                                    tracker.set_status(PlaybackStatus::Playing);
+                                    tracker.synced(position, rate);
+                                }
+                                None => tracker = Some(PositionTracker::new(position, rate, PlaybackStatus::Playing)),
+                            }
+                        }
+
+                        let Some(position) = tracker.as_ref().map(PositionTracker::position) else {
+                            continue;
+                        };
 
-                            // Send out PlayerPosition event.
-                            event_sender.send(Ok(MprisEvent::PlayerPosition(identity.clone(), position))).unwrap();
+                        // Send out PlayerTrackEnding event, once the track gets close to its end.
+                        if let Some(remaining) = lifecycle.ending(position, options.track_ending())
+                            && wants(&options, EventKind::PlayerTrackEnding)
+                            && event_sender.send(Ok(MprisEvent::PlayerTrackEnding(identity.clone(), remaining))).await.is_err()
+                        {
+                            return Ok(());
+                        }
+
+                        // Send out PlayerTrackFinished event, once the track is about to run out.
+                        if let Some(finished) = lifecycle.position(position, options.poll_interval())
+                            && wants(&options, EventKind::PlayerTrackFinished)
+                            && event_sender.send(Ok(MprisEvent::PlayerTrackFinished(identity.clone(), finished))).await.is_err()
+                        {
+                            return Ok(());
+                        }
+
+                        // Send out PlayerPosition event, if it moved enough.
+                        if wants(&options, EventKind::PlayerPosition)
+                            && options.position_threshold().passes(last_position, position)
+                        {
+                            last_position = Some(position);
+                            if event_sender.send(Ok(MprisEvent::PlayerPosition(identity.clone(), position))).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+
+                        // Polls faster close to the end of the track.
+                        if let Some(adaptive_polling) = options.adaptive_polling() {
+                            let remaining = metadata
+                                .as_ref()
+                                .and_then(|metadata| metadata.length().ok().flatten())
+                                .map(|length| length.saturating_sub(position));
+
+                            adapt_ticker(&mut tickler, adaptive_polling.interval(remaining));
                         }
                     },
                 }
             }
-        });
+
+            Ok(())
+        }
+    }
+
+    /// Gets all the properties of the player interface in one go.
+    ///
+    /// Uses a single `Properties.GetAll` call, falling back to getting the properties
+    /// one by one for the players that don't implement it right (see [`PlayerQuirks`]).
+    /// The optional properties the player doesn't have are left out.
+    pub async fn get_all(&self) -> MprisResult<HashMap<String, OwnedValue>> {
+        let interface = InterfaceName::from_static_str_unchecked(DBUS_MPRIS_PLAYER_INTERFACE_NAME);
+
+        if !self.quirks().broken_get_all() {
+            self.inner.stats.record_call(&self.inner.identity);
+            let properties = self.inner.properties_proxy.get_all(interface.clone()).await;
+
+            // Some players answer with nothing, instead of an error.
+            match properties {
+                Ok(properties) if properties.contains_key("PlaybackStatus") => {
+                    return Ok(properties);
+                }
+                _ => self.record_quirk(PlayerQuirks::set_broken_get_all),
+            }
+        }
+
+        let mut properties = HashMap::with_capacity(PLAYER_PROPERTIES.len());
+        for property in PLAYER_PROPERTIES {
+            self.inner.stats.record_call(&self.inner.identity);
+            if let Ok(value) = self
+                .inner
+                .properties_proxy
+                .get(interface.clone(), property)
+                .await
+            {
+                properties.insert(property.to_string(), value);
+            }
+        }
+
+        // Nothing at all means the player is gone, rather than broken.
+        if properties.is_empty() {
+            return Err(PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "*",
+                "The player has no properties",
+            ));
+        }
+
+        Ok(properties)
+    }
+
+    /// Gets the misbehaviors of the player found so far.
+    pub fn quirks(&self) -> PlayerQuirks {
+        *self
+            .inner
+            .quirks
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Records a misbehavior of the player.
+    fn record_quirk(&self, record: impl FnOnce(&mut PlayerQuirks)) {
+        record(
+            &mut self
+                .inner
+                .quirks
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()),
+        );
     }
 
     /// Metadata of player.
-    pub async fn metadata(&self) -> MprisResult<PlayerMetadata> {
-        let metadata: HashMap<String, zvariant::Value> = self
-            .player_proxy
-            .get_property("Metadata")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("Metadata", err.to_string()))?;
+    pub async fn metadata(&self) -> MprisResult<PlayerMetadata<'static>> {
+        let metadata = self.player_proxy().metadata().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Metadata", err.to_string())
+        })?;
 
-        Ok(PlayerMetadata::new(metadata))
+        Ok(owned_metadata(metadata))
     }
 
-    pub async fn play(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Play", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Play", err.to_string()))?;
+    pub async fn play(&self) -> MprisResult<()> {
+        if !self.can_play().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanPlay",
+                "Play",
+            ));
+        }
+
+        self.player_proxy().play().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Play", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn play_pause(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("PlayPause", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("PlayPause", err.to_string()))?;
+    pub async fn play_pause(&self) -> MprisResult<()> {
+        if !self.can_pause().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanPause",
+                "PlayPause",
+            ));
+        }
+
+        self.player_proxy().play_pause().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "PlayPause", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn pause(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Play", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Play", err.to_string()))?;
+    pub async fn pause(&self) -> MprisResult<()> {
+        if !self.can_pause().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanPause",
+                "Pause",
+            ));
+        }
+
+        self.player_proxy().pause().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Pause", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn stop(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Stop", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Stop", err.to_string()))?;
+    pub async fn stop(&self) -> MprisResult<()> {
+        if !self.can_control().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanControl",
+                "Stop",
+            ));
+        }
+
+        self.player_proxy().stop().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Stop", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn next(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Next", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Next", err.to_string()))?;
+    pub async fn next(&self) -> MprisResult<()> {
+        if !self.can_next().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanGoNext",
+                "Next",
+            ));
+        }
+
+        self.player_proxy().next().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Next", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn previous(&mut self) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Previous", &())
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Previous", err.to_string()))?;
+    pub async fn previous(&self) -> MprisResult<()> {
+        if !self.can_previous().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanGoPrevious",
+                "Previous",
+            ));
+        }
+
+        self.player_proxy().previous().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Previous", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn seek_forward(&mut self, offset: Duration) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Seek", &(offset.as_micros() as i64))
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Seek", err.to_string()))?;
+    /// Seeks by the offset in microseconds, forward if it's positive and backward if it's negative.
+    ///
+    /// Seeking past the end of the track goes to the next one, seeking before the start goes to the start.
+    pub async fn seek(&self, offset: i64) -> MprisResult<()> {
+        if !self.can_seek().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanSeek",
+                "Seek",
+            ));
+        }
+
+        self.player_proxy().seek(offset).await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Seek", err.to_string())
+        })?;
 
         Ok(())
     }
 
-    pub async fn seek_backward(&mut self, offset: Duration) -> MprisResult<()> {
-        self.player_proxy
-            .call_method("Seek", &(-(offset.as_micros() as i64)))
+    /// Seeks to the position within the current track, clamped to its length if it has one.
+    ///
+    /// Errors with [`PlayerError::NoTrack`] if nothing is loaded.
+    pub async fn seek_to(&self, position: Duration) -> MprisResult<()> {
+        let metadata = self.metadata().await?;
+
+        let track_id = metadata
+            .track_id()?
+            .filter(|track_id| track_id.as_ref() != NO_TRACK)
+            .ok_or_else(|| PlayerError::no_track(self.inner.identity.bus()))?;
+
+        let position = match metadata.length()? {
+            Some(length) => position.min(length),
+            None => position,
+        };
+
+        self.set_position(&track_id, position).await
+    }
+
+    /// Sets the position within the given track, the player ignores it if the track isn't the current one.
+    pub async fn set_position(&self, track_id: &TrackId, position: Duration) -> MprisResult<()> {
+        if !self.can_seek().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanSeek",
+                "SetPosition",
+            ));
+        }
+
+        let trackid = self.track_path(track_id.as_ref())?;
+
+        self.player_proxy()
+            .set_position(&trackid, position.as_micros() as i64)
             .await
-            .map_err(|err| PlayerError::failed_to_call_fn("Seek", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(
+                    self.inner.identity.bus(),
+                    "SetPosition",
+                    err.to_string(),
+                )
+            })?;
 
         Ok(())
     }
 
-    pub async fn set_position(&mut self, trackid: &str, position: Duration) -> MprisResult<()> {
-        let trackid = ObjectPath::try_from(trackid).map_err(|err| {
-            PlayerError::other(format!("Failed to create player track id: {err}"))
+    /// Sets the position within the current track, looking up its track id first.
+    ///
+    /// Errors with [`PlayerError::NoTrack`] if nothing is loaded.
+    pub async fn set_position_current(&self, position: Duration) -> MprisResult<()> {
+        let track_id = self
+            .metadata()
+            .await?
+            .track_id()?
+            .filter(|track_id| track_id.as_ref() != NO_TRACK)
+            .ok_or_else(|| PlayerError::no_track(self.inner.identity.bus()))?;
+
+        self.set_position(&track_id, position).await
+    }
+
+    /// Opens the uri (e.g., `file:///music/song.mp3`) in the player, which usually starts playing it.
+    ///
+    /// The player is free to ignore the uris it can't handle, see [`MprisPlayer::open_uri_checked`].
+    pub async fn open_uri(&self, uri: &str) -> MprisResult<()> {
+        self.player_proxy().open_uri(uri).await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "OpenUri", err.to_string())
         })?;
 
-        self.player_proxy
-            .call_method("SetPosition", &(trackid, position.as_micros() as i64))
-            .await
-            .map_err(|err| PlayerError::failed_to_call_fn("SetPosition", err.to_string()))?;
+        Ok(())
+    }
+
+    /// Opens the uri after checking it against what the player advertises, see [`MprisPlayer::check_uri`].
+    ///
+    /// Many players silently ignore the uris they can't handle,
+    /// this turns that into an [`PlayerError::UnsupportedUri`] error instead.
+    pub async fn open_uri_checked(&self, uri: &str) -> MprisResult<()> {
+        self.check_uri(uri).await?;
+        self.open_uri(uri).await
+    }
+
+    /// Checks the uri scheme against the `SupportedUriSchemes` of the player,
+    /// and its mime type against the `SupportedMimeTypes` when it can be guessed from the extension.
+    pub async fn check_uri(&self, uri: &str) -> MprisResult<()> {
+        let Some((scheme, rest)) = uri.split_once(':') else {
+            return Err(PlayerError::unsupported_uri(
+                self.inner.identity.bus(),
+                uri,
+                "Missing the uri scheme",
+            ));
+        };
+
+        let schemes = self.supported_uri_schemes().await?;
+        if !schemes
+            .iter()
+            .any(|supported| supported.eq_ignore_ascii_case(scheme))
+        {
+            return Err(PlayerError::unsupported_uri(
+                self.inner.identity.bus(),
+                uri,
+                format!("The {scheme} scheme is not one of: {}", schemes.join(", ")),
+            ));
+        }
+
+        // The mime type can only be told for uris ending with a known extension.
+        let Some(mime_type) = guess_mime_type(rest) else {
+            return Ok(());
+        };
+
+        // An empty list means the player didn't bother advertising them.
+        let mime_types = self.supported_mime_types().await?;
+        if !mime_types.is_empty() && !mime_types.iter().any(|supported| supported == mime_type) {
+            return Err(PlayerError::unsupported_uri(
+                self.inner.identity.bus(),
+                uri,
+                format!("The {mime_type} mime type is not supported"),
+            ));
+        }
 
         Ok(())
     }
 
-    pub async fn playback_status(&self) -> MprisResult<PlaybackStatus> {
-        let playback_status: String = self
-            .player_proxy
-            .get_property("PlaybackStatus")
+    /// The uri schemes the player can open, like `file` or `http`.
+    pub async fn supported_uri_schemes(&self) -> MprisResult<Vec<String>> {
+        let schemes = self
+            .root_proxy()
+            .supported_uri_schemes()
             .await
-            .map_err(|err| PlayerError::failed_to_get_prop("PlaybackStatus", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "SupportedUriSchemes",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(schemes)
+    }
 
-        Ok(PlaybackStatus::from_str(&playback_status)?)
+    /// The mime types the player can open, like `audio/mpeg`.
+    pub async fn supported_mime_types(&self) -> MprisResult<Vec<String>> {
+        let mime_types = self
+            .root_proxy()
+            .supported_mime_types()
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "SupportedMimeTypes",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(mime_types)
+    }
+
+    pub async fn playback_status(&self) -> MprisResult<PlaybackStatus> {
+        let playback_status: String =
+            self.player_proxy().playback_status().await.map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "PlaybackStatus",
+                    err.to_string(),
+                )
+            })?;
+
+        PlaybackStatus::from_str(&playback_status)
+            .map_err(|err| PlayerError::other(self.inner.identity.bus(), err.to_string()))
     }
 
     pub async fn loop_status(&self) -> MprisResult<LoopStatus> {
-        let loop_status: String = self
-            .player_proxy
-            .get_property("LoopStatus")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("LoopStatus", err.to_string()))?;
+        let loop_status: String = self.player_proxy().loop_status().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "LoopStatus",
+                err.to_string(),
+            )
+        })?;
 
-        Ok(LoopStatus::from_str(&loop_status)?)
+        LoopStatus::from_str(&loop_status)
+            .map_err(|err| PlayerError::other(self.inner.identity.bus(), err.to_string()))
     }
 
-    pub async fn set_loop_status(&mut self, loop_status: LoopStatus) -> MprisResult<()> {
+    pub async fn set_loop_status(&self, loop_status: LoopStatus) -> MprisResult<()> {
         if !self.can_control().await? {
-            return Err(PlayerError::failed_to_set_prop(
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanControl",
                 "LoopStatus",
-                "CanControl is false",
             ));
         }
 
-        self.player_proxy
-            .set_property("LoopStatus", loop_status.to_string())
+        self.player_proxy()
+            .set_loop_status(loop_status.as_ref())
             .await
-            .map_err(|err| PlayerError::failed_to_set_prop("LoopStatus", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_set_prop(
+                    self.inner.identity.bus(),
+                    "LoopStatus",
+                    err.to_string(),
+                )
+            })?;
 
         Ok(())
     }
 
     pub async fn shuffle(&self) -> MprisResult<bool> {
-        let shuffle: bool = self
-            .player_proxy
-            .get_property("Shuffle")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("Shuffle", err.to_string()))?;
+        let shuffle: bool = self.player_proxy().shuffle().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Shuffle", err.to_string())
+        })?;
 
         Ok(shuffle)
     }
 
-    pub async fn set_shuffle(&mut self, shuffle: bool) -> MprisResult<()> {
+    pub async fn set_shuffle(&self, shuffle: bool) -> MprisResult<()> {
         if !self.can_control().await? {
-            return Err(PlayerError::failed_to_set_prop(
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanControl",
                 "Shuffle",
-                "CanControl is false",
             ));
         }
 
-        self.player_proxy
-            .set_property("Shuffle", shuffle)
+        self.player_proxy()
+            .set_shuffle(shuffle)
             .await
-            .map_err(|err| PlayerError::failed_to_set_prop("Shuffle", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_set_prop(
+                    self.inner.identity.bus(),
+                    "Shuffle",
+                    err.to_string(),
+                )
+            })?;
 
         Ok(())
     }
 
     pub async fn volume(&self) -> MprisResult<f64> {
-        let volume: f64 = self
-            .player_proxy
-            .get_property("Volume")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("Volume", err.to_string()))?;
+        let volume: f64 = self.player_proxy().volume().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Volume", err.to_string())
+        })?;
 
         Ok(volume)
     }
 
-    pub async fn set_volume(&mut self, volume: f64) -> MprisResult<()> {
+    pub async fn set_volume(&self, volume: f64) -> MprisResult<()> {
         if !self.can_control().await? {
-            return Err(PlayerError::failed_to_set_prop(
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanControl",
                 "Volume",
-                "CanControl is false",
             ));
         }
 
-        self.player_proxy
-            .set_property("Volume", volume)
+        self.player_proxy()
+            .set_volume(volume)
             .await
-            .map_err(|err| PlayerError::failed_to_set_prop("Position", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_set_prop(
+                    self.inner.identity.bus(),
+                    "Volume",
+                    err.to_string(),
+                )
+            })?;
 
         Ok(())
     }
 
     pub async fn position(&self) -> MprisResult<Duration> {
-        let position: i64 = self
-            .player_proxy
-            .get_property("Position")
-            .await
-            .map_err(|err| PlayerError::failed_to_set_prop("Position", err.to_string()))?;
+        let position: i64 = self.player_proxy().position().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Position", err.to_string())
+        })?;
 
         Ok(Duration::from_micros(position as u64))
     }
 
     /// Playback Rate of player.
     pub async fn playback_rate(&self) -> MprisResult<f64> {
-        let rate: f64 = self
-            .player_proxy
-            .get_property("Rate")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("Rate", err.to_string()))?;
+        let rate: f64 = self.player_proxy().rate().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Rate", err.to_string())
+        })?;
 
         Ok(rate)
     }
@@ -468,9 +1506,10 @@ impl MprisPlayer {
     /// Set Playback Rate of player.
     pub async fn set_playback_rate(&self, rate: f64) -> MprisResult<()> {
         if !self.can_control().await? {
-            return Err(PlayerError::failed_to_set_prop(
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanControl",
                 "Rate",
-                "Cannot set the Rate when CanControl is false",
             ));
         }
 
@@ -479,114 +1518,731 @@ impl MprisPlayer {
 
         if rate < min_rate || rate > max_rate {
             return Err(PlayerError::failed_to_set_prop(
+                self.inner.identity.bus(),
                 "Rate",
                 "Cannot set the Rate when its passed the MinimumRate or MaximumRate bounds",
             ));
         }
 
-        self.player_proxy
-            .set_property("Rate", rate)
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("Rate", err.to_string()))?;
+        self.player_proxy().set_rate(rate).await.map_err(|err| {
+            PlayerError::failed_to_set_prop(self.inner.identity.bus(), "Rate", err.to_string())
+        })?;
 
         Ok(())
     }
 
     /// Minimum Playback Rate of player.
     pub async fn min_playback_rate(&self) -> MprisResult<f64> {
-        let min_rate: f64 = self
-            .player_proxy
-            .get_property("MinimumRate")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("MinimumRate", err.to_string()))?;
+        let min_rate: f64 = self.player_proxy().minimum_rate().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "MinimumRate",
+                err.to_string(),
+            )
+        })?;
 
         Ok(min_rate)
     }
 
     /// Maximum Playback Rate of player.
     pub async fn max_playback_rate(&self) -> MprisResult<f64> {
-        let max_rate: f64 = self
-            .player_proxy
-            .get_property("MaximumRate")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("MaximumRate", err.to_string()))?;
+        let max_rate: f64 = self.player_proxy().maximum_rate().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "MaximumRate",
+                err.to_string(),
+            )
+        })?;
 
         Ok(max_rate)
     }
 
     /// Can the player go next.
     pub async fn can_next(&self) -> MprisResult<bool> {
-        let can_go_next: bool = self
-            .player_proxy
-            .get_property("CanGoNext")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanGoNext", err.to_string()))?;
+        let can_go_next: bool = self.player_proxy().can_go_next().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanGoNext", err.to_string())
+        })?;
 
         Ok(can_go_next)
     }
 
     /// Can the player go previous.
     pub async fn can_previous(&self) -> MprisResult<bool> {
-        let can_go_previous: bool = self
-            .player_proxy
-            .get_property("CanGoPrevious")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanGoPrevious", err.to_string()))?;
+        let can_go_previous: bool = self.player_proxy().can_go_previous().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "CanGoPrevious",
+                err.to_string(),
+            )
+        })?;
 
         Ok(can_go_previous)
     }
 
     /// Can the player play.
     pub async fn can_play(&self) -> MprisResult<bool> {
-        let can_play: bool = self
-            .player_proxy
-            .get_property("CanPlay")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanPlay", err.to_string()))?;
+        let can_play: bool = self.player_proxy().can_play().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanPlay", err.to_string())
+        })?;
 
         Ok(can_play)
     }
 
     /// Can the player pause.
     pub async fn can_pause(&self) -> MprisResult<bool> {
-        let can_pause: bool = self
-            .player_proxy
-            .get_property("CanPause")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanPause", err.to_string()))?;
+        let can_pause: bool = self.player_proxy().can_pause().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanPause", err.to_string())
+        })?;
 
         Ok(can_pause)
     }
 
     /// Can the player seek.
     pub async fn can_seek(&self) -> MprisResult<bool> {
-        let can_seek: bool = self
-            .player_proxy
-            .get_property("CanSeek")
-            .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanSeek", err.to_string()))?;
+        let can_seek: bool = self.player_proxy().can_seek().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanSeek", err.to_string())
+        })?;
 
         Ok(can_seek)
     }
 
+    /// Gets the playback status, the metadata and the volume of the player at once, with a single `GetAll`.
+    pub async fn snapshot(&self) -> MprisResult<PlayerSnapshot> {
+        let properties = self.get_all().await?;
+
+        Ok(PlayerSnapshot::new(properties))
+    }
+
+    /// Gets everything a status widget shows about the player at once, with a single `GetAll`.
+    ///
+    /// Saves the round trip per property of the getters, see [`PlayerState`].
+    pub async fn state(&self) -> MprisResult<PlayerState> {
+        let properties = self.get_all().await?;
+
+        Ok(PlayerState::new(properties))
+    }
+
+    /// Gets every `Can*` capability of the player at once, with a single `GetAll`.
+    pub async fn capabilities(&self) -> MprisResult<Capabilities> {
+        let properties = self.get_all().await?;
+
+        Ok(Capabilities::new(&properties))
+    }
+
     /// Can the player be controlled.
     pub async fn can_control(&self) -> MprisResult<bool> {
-        let can_control: bool = self
+        let can_control: bool = self.player_proxy().can_control().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "CanControl",
+                err.to_string(),
+            )
+        })?;
+
+        Ok(can_control)
+    }
+
+    /// Brings the player's user interface to the front.
+    pub async fn raise(&self) -> MprisResult<()> {
+        if !self.can_raise().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanRaise",
+                "Raise",
+            ));
+        }
+
+        self.root_proxy().raise().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Raise", err.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// Makes the player quit, it'll get detached once it's gone.
+    pub async fn quit(&self) -> MprisResult<()> {
+        if !self.can_quit().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanQuit",
+                "Quit",
+            ));
+        }
+
+        self.root_proxy().quit().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Quit", err.to_string())
+        })?;
+
+        Ok(())
+    }
+
+    /// The human readable name of the player (e.g., `Spotify`), its mpris `Identity`.
+    ///
+    /// Gets cached after the first call, a watched player drops the cache when its `Identity` changes.
+    pub async fn display_name(&self) -> MprisResult<String> {
+        if let Some(display_name) = self
+            .inner
+            .display_name
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+        {
+            return Ok(display_name);
+        }
+
+        let display_name = self.root_proxy().identity().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Identity", err.to_string())
+        })?;
+
+        *self
+            .inner
+            .display_name
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(display_name.clone());
+
+        Ok(display_name)
+    }
+
+    /// The basename of the player's desktop file (e.g., `spotify` for `spotify.desktop`).
+    pub async fn desktop_entry(&self) -> MprisResult<String> {
+        let desktop_entry = self.root_proxy().desktop_entry().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "DesktopEntry",
+                err.to_string(),
+            )
+        })?;
+
+        Ok(desktop_entry)
+    }
+
+    /// The icon of the player, resolved from its desktop file (see [`resolve_desktop_icon`]).
+    ///
+    /// Returns None if the player has no `DesktopEntry`, or its desktop file can't be found.
+    pub async fn desktop_icon(&self) -> MprisResult<Option<DesktopIcon>> {
+        let desktop_entry = match self.desktop_entry().await {
+            Ok(desktop_entry) => desktop_entry,
+
+            // The property is optional.
+            Err(MprisError::PlayerErr(PlayerError::FailedToGetProp(..))) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        Ok(resolve_desktop_icon(&desktop_entry).await)
+    }
+
+    /// Is the player fullscreen.
+    pub async fn fullscreen(&self) -> MprisResult<bool> {
+        let fullscreen = self.root_proxy().fullscreen().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "Fullscreen",
+                err.to_string(),
+            )
+        })?;
+
+        Ok(fullscreen)
+    }
+
+    pub async fn set_fullscreen(&self, fullscreen: bool) -> MprisResult<()> {
+        if !self.can_set_fullscreen().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanSetFullscreen",
+                "Fullscreen",
+            ));
+        }
+
+        self.root_proxy()
+            .set_fullscreen(fullscreen)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_set_prop(
+                    self.inner.identity.bus(),
+                    "Fullscreen",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Can the player quit.
+    pub async fn can_quit(&self) -> MprisResult<bool> {
+        let can_quit = self.root_proxy().can_quit().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanQuit", err.to_string())
+        })?;
+
+        Ok(can_quit)
+    }
+
+    /// Can the player be raised.
+    pub async fn can_raise(&self) -> MprisResult<bool> {
+        let can_raise = self.root_proxy().can_raise().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "CanRaise", err.to_string())
+        })?;
+
+        Ok(can_raise)
+    }
+
+    /// Can the player be made fullscreen.
+    pub async fn can_set_fullscreen(&self) -> MprisResult<bool> {
+        let can_set_fullscreen = self
+            .root_proxy()
+            .can_set_fullscreen()
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "CanSetFullscreen",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(can_set_fullscreen)
+    }
+
+    /// Does the player have a track list.
+    pub async fn has_track_list(&self) -> MprisResult<bool> {
+        let has_track_list = self.root_proxy().has_track_list().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(
+                self.inner.identity.bus(),
+                "HasTrackList",
+                err.to_string(),
+            )
+        })?;
+
+        Ok(has_track_list)
+    }
+
+    /// The ids of the tracks in the track list.
+    pub async fn tracks(&self) -> MprisResult<Vec<TrackId>> {
+        let tracks = self.track_list_proxy().tracks().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Tracks", err.to_string())
+        })?;
+
+        Ok(tracks
+            .into_iter()
+            .map(|track_id| TrackId::new(track_id.as_str()))
+            .collect())
+    }
+
+    /// The metadata of the tracks in the track list, in the same order as the ids.
+    ///
+    /// Gets them all in a single `GetTracksMetadata` call, the ids usually come from [`MprisPlayer::tracks`].
+    pub async fn tracks_metadata(
+        &self,
+        track_ids: &[TrackId],
+    ) -> MprisResult<Vec<PlayerMetadata<'static>>> {
+        let track_ids = track_ids
+            .iter()
+            .map(|track_id| self.track_path(track_id.as_ref()))
+            .collect::<MprisResult<Vec<_>>>()?;
+
+        let tracks_metadata = self
+            .track_list_proxy()
+            .get_tracks_metadata(&track_ids)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(
+                    self.inner.identity.bus(),
+                    "GetTracksMetadata",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(tracks_metadata
+            .into_iter()
+            .map(|metadata| {
+                PlayerMetadata::new(
+                    metadata
+                        .into_iter()
+                        .map(|(key, value)| (key, value.into()))
+                        .collect(),
+                )
+            })
+            .collect())
+    }
+
+    /// Can the tracks of the track list be added and removed.
+    pub async fn can_edit_tracks(&self) -> MprisResult<bool> {
+        let can_edit_tracks = self
+            .track_list_proxy()
+            .can_edit_tracks()
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "CanEditTracks",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(can_edit_tracks)
+    }
+
+    /// Adds the uri to the track list after the given track, or first when there's none.
+    pub async fn add_track(
+        &self,
+        uri: &str,
+        after: Option<&TrackId>,
+        set_as_current: bool,
+    ) -> MprisResult<()> {
+        if !self.can_edit_tracks().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanEditTracks",
+                "AddTrack",
+            ));
+        }
+
+        let after = self.track_path(after.map_or(NO_TRACK, AsRef::as_ref))?;
+
+        self.track_list_proxy()
+            .add_track(uri, &after, set_as_current)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(
+                    self.inner.identity.bus(),
+                    "AddTrack",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Removes the track from the track list.
+    pub async fn remove_track(&self, track_id: &TrackId) -> MprisResult<()> {
+        if !self.can_edit_tracks().await? {
+            return Err(PlayerError::capability_denied(
+                self.inner.identity.bus(),
+                "CanEditTracks",
+                "RemoveTrack",
+            ));
+        }
+
+        let track_id = self.track_path(track_id.as_ref())?;
+
+        self.track_list_proxy()
+            .remove_track(&track_id)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(
+                    self.inner.identity.bus(),
+                    "RemoveTrack",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Skips to the track of the track list.
+    ///
+    /// Unlike adding and removing tracks, this works even when [`MprisPlayer::can_edit_tracks`] is false.
+    pub async fn go_to(&self, track_id: &TrackId) -> MprisResult<()> {
+        let track_id = self.track_path(track_id.as_ref())?;
+
+        self.track_list_proxy()
+            .go_to(&track_id)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(self.inner.identity.bus(), "GoTo", err.to_string())
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists `max_count` of the player's playlists from `index`, in the given order.
+    ///
+    /// The player may not support every ordering, see [`MprisPlayer::playlist_orderings`].
+    pub async fn get_playlists(
+        &self,
+        index: u32,
+        max_count: u32,
+        ordering: PlaylistOrdering,
+        reverse: bool,
+    ) -> MprisResult<Vec<Playlist>> {
+        let playlists = self
+            .playlists_proxy()
+            .get_playlists(index, max_count, ordering.as_ref(), reverse)
+            .await
+            .map_err(|err| self.playlists_err("GetPlaylists", err))?;
+
+        Ok(playlists.into_iter().map(Playlist::from).collect())
+    }
+
+    /// Makes the playlist the current one, which usually starts playing it.
+    pub async fn activate_playlist(&self, playlist_id: &str) -> MprisResult<()> {
+        let playlist_id = ObjectPath::try_from(playlist_id).map_err(|err| {
+            PlayerError::other(
+                self.inner.identity.bus(),
+                format!("Failed to create player playlist id: {err}"),
+            )
+        })?;
+
+        self.playlists_proxy()
+            .activate_playlist(&playlist_id)
+            .await
+            .map_err(|err| self.playlists_err("ActivatePlaylist", err))?;
+
+        Ok(())
+    }
+
+    /// The orderings the player can list its playlists in.
+    ///
+    /// The orderings outside of the spec are left out.
+    pub async fn playlist_orderings(&self) -> MprisResult<Vec<PlaylistOrdering>> {
+        let orderings = self.playlists_proxy().orderings().await.map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), "Orderings", err.to_string())
+        })?;
+
+        Ok(orderings
+            .iter()
+            .filter_map(|ordering| PlaylistOrdering::from_str(ordering).ok())
+            .collect())
+    }
+
+    /// The number of playlists the player has.
+    pub async fn playlist_count(&self) -> MprisResult<u32> {
+        let playlist_count = self
+            .playlists_proxy()
+            .playlist_count()
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "PlaylistCount",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(playlist_count)
+    }
+
+    /// The playlist currently being played, None if there is none (or the player can't tell).
+    pub async fn active_playlist(&self) -> MprisResult<Option<Playlist>> {
+        let (valid, playlist) = self
+            .playlists_proxy()
+            .active_playlist()
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(
+                    self.inner.identity.bus(),
+                    "ActivePlaylist",
+                    err.to_string(),
+                )
+            })?;
+
+        Ok(valid.then(|| Playlist::from(playlist)))
+    }
+
+    /// Gets who owns the bus of the player, see [`PlayerCredentials`].
+    pub async fn credentials(&self) -> MprisResult<PlayerCredentials> {
+        let dbus_proxy = proxies::create_dbus_proxy(self.connection()).await?;
+
+        let bus =
+            BusName::try_from(self.inner.identity.bus()).map_err(|_| MprisError::InvalidBusName)?;
+        let credentials = dbus_proxy
+            .get_connection_credentials(bus)
+            .await
+            .map_err(|err| {
+                MprisError::FailedToCallFn("GetConnectionCredentials".into(), err.to_string())
+            })?;
+
+        Ok(PlayerCredentials::new(credentials))
+    }
+
+    /// Probes the player against the MPRIS spec, see [`ComplianceReport`].
+    ///
+    /// Only reads from the player, it's safe to run on one that's in use.
+    pub async fn check_compliance(&self) -> MprisResult<ComplianceReport> {
+        let introspectable_proxy =
+            proxies::create_introspectable_proxy(self.connection(), self.inner.identity.bus())
+                .await?;
+
+        Ok(ComplianceReport::probe(
+            self.inner.identity.bus(),
+            &self.inner.properties_proxy,
+            &introspectable_proxy,
+        )
+        .await)
+    }
+
+    /// Calls any method on the player's object, for the extensions outside of the MPRIS spec.
+    ///
+    /// The arguments are a tuple (e.g., `&(42u32, "name")`, or `&()` for none),
+    /// and so is the reply when the method returns more than one value.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn example(player: &mprizzle::MprisPlayer) -> mprizzle::MprisResult<()> {
+    /// // Any method works the same, a standard one is used here.
+    /// player
+    ///     .call_raw::<_, ()>("org.mpris.MediaPlayer2.Player", "Seek", &(5_000_000i64,))
+    ///     .await?;
+    ///
+    /// let volume: f64 = player
+    ///     .get_raw_property("org.mpris.MediaPlayer2.Player", "Volume")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn call_raw<A, R>(&self, interface: &str, method: &str, args: &A) -> MprisResult<R>
+    where
+        A: Serialize + DynamicType,
+        R: DeserializeOwned + Type,
+    {
+        self.inner.stats.record_call(&self.inner.identity);
+        let reply = self
+            .inner
             .player_proxy
-            .get_property("CanControl")
+            .inner()
+            .connection()
+            .call_method(
+                Some(self.inner.identity.bus()),
+                DBUS_MPRIS_INTERFACE_PATH,
+                Some(interface),
+                method,
+                args,
+            )
             .await
-            .map_err(|err| PlayerError::failed_to_get_prop("CanControl", err.to_string()))?;
+            .map_err(|err| {
+                PlayerError::failed_to_call_fn(self.inner.identity.bus(), method, err.to_string())
+            })?;
+
+        reply.body().deserialize().map_err(|err| {
+            PlayerError::other(
+                self.inner.identity.bus(),
+                format!("Failed to parse the reply of {method}: {err}"),
+            )
+        })
+    }
 
-        Ok(can_control)
+    /// Gets any property of the player's object, for the extensions outside of the MPRIS spec.
+    pub async fn get_raw_property<T>(&self, interface: &str, name: &str) -> MprisResult<T>
+    where
+        T: TryFrom<OwnedValue>,
+        T::Error: std::fmt::Display,
+    {
+        let interface = InterfaceName::try_from(interface).map_err(|err| {
+            PlayerError::failed_to_get_prop(self.inner.identity.bus(), name, err.to_string())
+        })?;
+
+        self.inner.stats.record_call(&self.inner.identity);
+        let value = self
+            .inner
+            .properties_proxy
+            .get(interface, name)
+            .await
+            .map_err(|err| {
+                PlayerError::failed_to_get_prop(self.inner.identity.bus(), name, err.to_string())
+            })?;
+
+        T::try_from(value).map_err(|err| {
+            PlayerError::other(
+                self.inner.identity.bus(),
+                format!("Failed to parse the {name} property: {err}"),
+            )
+        })
+    }
+
+    /// Introspects the player to find out which MPRIS interfaces and optional properties it implements.
+    ///
+    /// Meant for adapting a user interface to the player, e.g. hiding the queue of the players
+    /// without a track list, instead of running into [`PlayerError::UnsupportedInterface`].
+    pub async fn interfaces(&self) -> MprisResult<PlayerInterfaces> {
+        let introspectable_proxy =
+            proxies::create_introspectable_proxy(self.connection(), self.inner.identity.bus())
+                .await?;
+
+        self.inner.stats.record_call(&self.inner.identity);
+        let xml = introspectable_proxy.introspect().await.map_err(|err| {
+            PlayerError::failed_to_call_fn(self.inner.identity.bus(), "Introspect", err.to_string())
+        })?;
+
+        Ok(PlayerInterfaces::from_xml(&xml))
+    }
+
+    /// Turns a track id into the object path D-Bus wants.
+    fn track_path<'t>(&self, track_id: &'t str) -> MprisResult<ObjectPath<'t>> {
+        ObjectPath::try_from(track_id).map_err(|err| {
+            PlayerError::other(
+                self.inner.identity.bus(),
+                format!("Failed to create player track id: {err}"),
+            )
+        })
+    }
+
+    /// Maps the error of a playlists call, telling apart the players without playlists.
+    fn playlists_err(&self, name: &str, err: zbus::Error) -> MprisError {
+        if is_unimplemented(&err) {
+            return PlayerError::unsupported_interface(
+                self.inner.identity.bus(),
+                DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME,
+            );
+        }
+
+        PlayerError::failed_to_call_fn(self.inner.identity.bus(), name, err.to_string())
+    }
+
+    /// Gets the playlists proxy, counting the D-Bus call about to be made with it.
+    fn playlists_proxy(&self) -> &PlaylistsProxy<'static> {
+        self.inner.stats.record_call(&self.inner.identity);
+        &self.inner.playlists_proxy
+    }
+
+    /// Gets the track list proxy, counting the D-Bus call about to be made with it.
+    fn track_list_proxy(&self) -> &TrackListProxy<'static> {
+        self.inner.stats.record_call(&self.inner.identity);
+        &self.inner.track_list_proxy
+    }
+
+    /// Gets the root proxy, counting the D-Bus call about to be made with it.
+    fn root_proxy(&self) -> &MediaPlayer2Proxy<'static> {
+        self.inner.stats.record_call(&self.inner.identity);
+        &self.inner.root_proxy
+    }
+
+    /// Gets the player proxy, counting the D-Bus call about to be made with it.
+    fn player_proxy(&self) -> &PlayerProxy<'static> {
+        self.inner.stats.record_call(&self.inner.identity);
+        &self.inner.player_proxy
     }
 
     /// Gets the shared mpris connection.
     fn connection(&self) -> Arc<Mutex<Connection>> {
-        Arc::clone(&self.connection)
+        Arc::clone(&self.inner.connection)
     }
 
     /// Gets the identity of the player.
     pub fn identity(&self) -> &PlayerIdentity {
-        &self.identity
+        &self.inner.identity
     }
 }
+
+/// Guesses the mime type of the uri from its extension.
+fn guess_mime_type(uri: &str) -> Option<&'static str> {
+    // Ignores the query and the fragment, if any.
+    let path = uri.split(['?', '#']).next()?;
+    let file_name = path.rsplit('/').next()?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+
+    let mime_type = match extension.to_ascii_lowercase().as_str() {
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "oga" => "audio/ogg",
+        "opus" => "audio/opus",
+        "wav" => "audio/x-wav",
+        "m4a" => "audio/mp4",
+        "aac" => "audio/aac",
+        "mp4" => "video/mp4",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        _ => return None,
+    };
+
+    Some(mime_type)
+}
@@ -0,0 +1,100 @@
+use core::fmt;
+use std::str::FromStr;
+
+use zvariant::OwnedObjectPath;
+
+use crate::MprisError;
+
+/// A playlist of a player, as listed by [`crate::MprisPlayer::get_playlists`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playlist {
+    /// The object path identifying the playlist.
+    id: String,
+
+    /// The name shown to the user.
+    name: String,
+
+    /// The uri of the playlist icon, players send an empty one when there's none.
+    icon: Option<String>,
+}
+
+impl Playlist {
+    /// Gets the id of the playlist, to be passed back to the player.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Gets the name of the playlist.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the icon uri of the playlist.
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+}
+
+impl From<(OwnedObjectPath, String, String)> for Playlist {
+    fn from((id, name, icon): (OwnedObjectPath, String, String)) -> Self {
+        Self {
+            id: id.to_string(),
+            name,
+            icon: (!icon.is_empty()).then_some(icon),
+        }
+    }
+}
+
+/// The order playlists get listed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistOrdering {
+    /// By name.
+    Alphabetical,
+
+    /// By when they were created.
+    CreationDate,
+
+    /// By when they were last modified.
+    ModifiedDate,
+
+    /// By when they were last played.
+    LastPlayDate,
+
+    /// The order the user picked in the player.
+    UserDefined,
+}
+
+impl FromStr for PlaylistOrdering {
+    type Err = MprisError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "alphabetical" => Ok(PlaylistOrdering::Alphabetical),
+            "created" => Ok(PlaylistOrdering::CreationDate),
+            "modified" => Ok(PlaylistOrdering::ModifiedDate),
+            "played" => Ok(PlaylistOrdering::LastPlayDate),
+            "user" => Ok(PlaylistOrdering::UserDefined),
+            _ => Err(MprisError::Other(
+                "PlaylistOrdering is not Alphabetical, Created, Modified, Played or User.".into(),
+            )),
+        }
+    }
+}
+
+impl AsRef<str> for PlaylistOrdering {
+    fn as_ref(&self) -> &str {
+        match self {
+            PlaylistOrdering::Alphabetical => "Alphabetical",
+            PlaylistOrdering::CreationDate => "Created",
+            PlaylistOrdering::ModifiedDate => "Modified",
+            PlaylistOrdering::LastPlayDate => "Played",
+            PlaylistOrdering::UserDefined => "User",
+        }
+    }
+}
+
+impl fmt::Display for PlaylistOrdering {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_ref())
+    }
+}
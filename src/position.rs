@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+use crate::{MprisPlayer, MprisResult, PlaybackStatus};
+
+/// Works out the position of a player locally, instead of asking the player for it.
+///
+/// Moves the last known position forward with a monotonic clock at the playback rate
+/// while playing, so it only has to be told about the changes: the seeks, the playback
+/// status and rate changes, and the track changes. The watch tasks use it too, for the
+/// positions they send out, see [`crate::MprisOptions::with_local_position`].
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::{Mpris, MprisEvent, PositionTracker};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::new().await?;
+///     let player = mpris.list_players().await?.remove(0);
+///     let mut tracker = PositionTracker::from_player(&player).await?;
+///
+///     mpris.watch();
+///
+///     while let Ok(event) = mpris.recv().await? {
+///         match event {
+///             MprisEvent::PlayerSeeked(_) => tracker.seeked(player.position().await?),
+///             MprisEvent::PlayerPlaybackStatusChanged(_, _, status) => tracker.set_status(status),
+///             _ => {}
+///         }
+///
+///         println!("{}", tracker.position().as_secs());
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionTracker {
+    /// The last known position, and when it was known.
+    position: Duration,
+    at: Instant,
+
+    /// The playback rate.
+    rate: f64,
+
+    /// The playback status, the position only moves while playing.
+    status: PlaybackStatus,
+}
+
+impl PositionTracker {
+    /// Starts tracking from a known position.
+    pub fn new(position: Duration, rate: f64, status: PlaybackStatus) -> Self {
+        Self {
+            position,
+            at: Instant::now(),
+            rate,
+            status,
+        }
+    }
+
+    /// Starts tracking from the current position of the player.
+    ///
+    /// The players without a `Rate` are taken as playing at the normal one.
+    pub async fn from_player(player: &MprisPlayer) -> MprisResult<Self> {
+        let status = player.playback_status().await?;
+        let position = player.position().await?;
+        let rate = player.playback_rate().await.unwrap_or(1.0);
+
+        Ok(Self::new(position, rate, status))
+    }
+
+    /// Gets the current position.
+    pub fn position(&self) -> Duration {
+        match self.status {
+            PlaybackStatus::Playing => {
+                self.position + self.at.elapsed().mul_f64(self.rate.max(0.0))
+            }
+            _ => self.position,
+        }
+    }
+
+    /// Gets the playback status.
+    pub fn status(&self) -> &PlaybackStatus {
+        &self.status
+    }
+
+    /// Gets the playback rate.
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Moves to the position the player seeked to, or got read from the player.
+    pub fn seeked(&mut self, position: Duration) {
+        self.position = position;
+        self.at = Instant::now();
+    }
+
+    /// Resyncs with the position and the rate read from the player.
+    ///
+    /// A position behind the current one is taken as the player lagging behind, and gets ignored,
+    /// so the position only goes backwards on a seek, a stop or a track change.
+    pub fn synced(&mut self, position: Duration, rate: f64) {
+        self.seeked(position.max(self.position()));
+        self.rate = rate;
+    }
+
+    /// Changes the playback status, a stopped player goes back to the start.
+    pub fn set_status(&mut self, status: PlaybackStatus) {
+        let position = match status {
+            PlaybackStatus::Stopped => Duration::ZERO,
+            _ => self.position(),
+        };
+
+        self.seeked(position);
+        self.status = status;
+    }
+
+    /// Changes the playback rate, from the current position on.
+    pub fn set_rate(&mut self, rate: f64) {
+        self.seeked(self.position());
+        self.rate = rate;
+    }
+
+    /// Starts over for another track.
+    pub fn track_changed(&mut self) {
+        self.seeked(Duration::ZERO);
+    }
+}
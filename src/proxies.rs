@@ -1,94 +1,436 @@
-use std::sync::Arc;
+//! Typed D-Bus proxies for the mpris interfaces.
+//!
+//! These are generated by [`macro@zbus::proxy`], and are what [`crate::MprisPlayer`] uses under the hood.
+//! Only the proxy types get re-exported, for reaching the parts of the spec mprizzle doesn't wrap (yet),
+//! like the typed signal and property streams.
 
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{Stream, StreamExt, future};
 use tokio::sync::Mutex;
-use zbus::{Connection, Proxy};
+use zbus::{
+    Connection, MatchRule, MessageStream,
+    fdo::{DBusProxy, IntrospectableProxy, NameOwnerChanged, PropertiesProxy},
+    message::Type,
+    proxy::CacheProperties,
+    zvariant::{ObjectPath, OwnedObjectPath, OwnedValue},
+};
 
 use crate::{MprisError, MprisResult};
 
-pub const DBUS_MPRIS_INTERFACE_NAME: &str = "org.mpris.MediaPlayer2";
-pub const DBUS_MPRIS_INTERFACE_PATH: &str = "/org/mpris/MediaPlayer2";
+pub(crate) const DBUS_MPRIS_INTERFACE_NAME: &str = "org.mpris.MediaPlayer2";
+pub(crate) const DBUS_MPRIS_PLAYER_INTERFACE_NAME: &str = "org.mpris.MediaPlayer2.Player";
+pub(crate) const DBUS_MPRIS_TRACK_LIST_INTERFACE_NAME: &str = "org.mpris.MediaPlayer2.TrackList";
+pub(crate) const DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME: &str = "org.mpris.MediaPlayer2.Playlists";
+pub(crate) const DBUS_MPRIS_INTERFACE_PATH: &str = "/org/mpris/MediaPlayer2";
 
 /// Represents errors that can occur in MPRIX Proxy operations.
-#[derive(Debug, thiserror::Error)]
+///
+/// Like [`crate::PlayerError`], every error carries the bus name the proxy was for first.
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ProxyError {
-    #[error("Failed to create: {0} proxy.")]
-    FailedToCreate(String),
+    #[error("{0}: Failed to create: {1} proxy.")]
+    FailedToCreate(String, String),
 
-    #[error("{0}")]
-    Other(#[from] zbus::Error),
+    #[error("{0}: {1}")]
+    Other(String, zbus::Error),
 }
 
 impl ProxyError {
-    pub fn failed_to_create(proxy: impl Into<String>) -> MprisError {
-        MprisError::ProxyErr(ProxyError::FailedToCreate(proxy.into()))
+    pub(crate) fn failed_to_create(bus: &str, proxy: impl Into<String>) -> MprisError {
+        MprisError::ProxyErr(ProxyError::FailedToCreate(bus.into(), proxy.into()))
+    }
+
+    pub(crate) fn other(bus: &str, other: zbus::Error) -> MprisError {
+        MprisError::ProxyErr(ProxyError::Other(bus.into(), other))
     }
 
-    pub fn other(other: zbus::Error) -> MprisError {
-        MprisError::ProxyErr(ProxyError::Other(other))
+    /// Gets the bus name the proxy was for.
+    pub fn bus(&self) -> &str {
+        match self {
+            ProxyError::FailedToCreate(bus, _) | ProxyError::Other(bus, _) => bus,
+        }
     }
 }
 
-/// Proxy for "org.freedesktop.DBUS" interface.
-pub async fn create_dbus_proxy(
+/// The "org.mpris.MediaPlayer2" root interface.
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+pub trait MediaPlayer2 {
+    fn raise(&self) -> zbus::Result<()>;
+
+    fn quit(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn can_quit(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn fullscreen(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn set_fullscreen(&self, fullscreen: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn can_set_fullscreen(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_raise(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn identity(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn desktop_entry(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> zbus::Result<Vec<String>>;
+}
+
+/// The "org.mpris.MediaPlayer2.Player" interface.
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.Player",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+pub trait Player {
+    fn next(&self) -> zbus::Result<()>;
+
+    fn previous(&self) -> zbus::Result<()>;
+
+    fn pause(&self) -> zbus::Result<()>;
+
+    fn play_pause(&self) -> zbus::Result<()>;
+
+    fn stop(&self) -> zbus::Result<()>;
+
+    fn play(&self) -> zbus::Result<()>;
+
+    fn seek(&self, offset: i64) -> zbus::Result<()>;
+
+    fn set_position(&self, track_id: &ObjectPath<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(name = "OpenUri")]
+    fn open_uri(&self, uri: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn seeked(&self, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn loop_status(&self) -> zbus::Result<String>;
+
+    #[zbus(property)]
+    fn set_loop_status(&self, loop_status: &str) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn rate(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn set_rate(&self, rate: f64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn shuffle(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn set_shuffle(&self, shuffle: bool) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn metadata(&self) -> zbus::Result<HashMap<String, OwnedValue>>;
+
+    #[zbus(property)]
+    fn volume(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn set_volume(&self, volume: f64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn position(&self) -> zbus::Result<i64>;
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> zbus::Result<f64>;
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_play(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_pause(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_seek(&self) -> zbus::Result<bool>;
+
+    #[zbus(property)]
+    fn can_control(&self) -> zbus::Result<bool>;
+}
+
+/// The "org.mpris.MediaPlayer2.TrackList" interface.
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.TrackList",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+pub trait TrackList {
+    fn get_tracks_metadata(
+        &self,
+        track_ids: &[ObjectPath<'_>],
+    ) -> zbus::Result<Vec<HashMap<String, OwnedValue>>>;
+
+    fn add_track(
+        &self,
+        uri: &str,
+        after_track: &ObjectPath<'_>,
+        set_as_current: bool,
+    ) -> zbus::Result<()>;
+
+    fn remove_track(&self, track_id: &ObjectPath<'_>) -> zbus::Result<()>;
+
+    fn go_to(&self, track_id: &ObjectPath<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn track_list_replaced(
+        &self,
+        tracks: Vec<ObjectPath<'_>>,
+        current_track: ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn track_added(
+        &self,
+        metadata: HashMap<String, OwnedValue>,
+        after_track: ObjectPath<'_>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn track_removed(&self, track_id: ObjectPath<'_>) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn track_metadata_changed(
+        &self,
+        track_id: ObjectPath<'_>,
+        metadata: HashMap<String, OwnedValue>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn tracks(&self) -> zbus::Result<Vec<OwnedObjectPath>>;
+
+    #[zbus(property)]
+    fn can_edit_tracks(&self) -> zbus::Result<bool>;
+}
+
+/// The "org.mpris.MediaPlayer2.Playlists" interface.
+#[zbus::proxy(
+    interface = "org.mpris.MediaPlayer2.Playlists",
+    default_path = "/org/mpris/MediaPlayer2"
+)]
+pub trait Playlists {
+    fn activate_playlist(&self, playlist_id: &ObjectPath<'_>) -> zbus::Result<()>;
+
+    fn get_playlists(
+        &self,
+        index: u32,
+        max_count: u32,
+        order: &str,
+        reverse_order: bool,
+    ) -> zbus::Result<Vec<(OwnedObjectPath, String, String)>>;
+
+    #[zbus(signal)]
+    fn playlist_changed(&self, playlist: (OwnedObjectPath, String, String)) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playlist_count(&self) -> zbus::Result<u32>;
+
+    #[zbus(property)]
+    fn orderings(&self) -> zbus::Result<Vec<String>>;
+
+    #[zbus(property)]
+    fn active_playlist(&self) -> zbus::Result<(bool, (OwnedObjectPath, String, String))>;
+}
+
+/// Proxy for "org.freedesktop.DBus" interface.
+pub(crate) async fn create_dbus_proxy(
     shared_connection: Arc<Mutex<Connection>>,
-) -> MprisResult<Proxy<'static>> {
+) -> MprisResult<DBusProxy<'static>> {
     let connection = shared_connection
         .try_lock()
         .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
 
-    let proxy = Proxy::new(
-        &*connection,
-        "org.freedesktop.DBus",
-        "/org/freedesktop/DBus",
-        "org.freedesktop.DBus",
-    )
-    .await
-    .map_err(|_| ProxyError::failed_to_create("org.freedesktop.DBus"))?;
+    let proxy = DBusProxy::new(&connection).await.map_err(|_| {
+        ProxyError::failed_to_create("org.freedesktop.DBus", "org.freedesktop.DBus")
+    })?;
 
     Ok(proxy)
 }
 
+/// Receives the "NameOwnerChanged" signals of the mpris buses only.
+///
+/// The bus daemon filters them with an `arg0namespace` match rule,
+/// so the other names changing owners don't wake the process up.
+pub(crate) async fn receive_mpris_name_owner_changed(
+    shared_connection: Arc<Mutex<Connection>>,
+) -> MprisResult<impl Stream<Item = NameOwnerChanged> + Send + Unpin + 'static> {
+    let connection = shared_connection
+        .try_lock()
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
+
+    let failed = |err: zbus::Error| {
+        MprisError::Other(format!(
+            "Failed to create a stream for NameOwnerChanged: {err}"
+        ))
+    };
+
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender("org.freedesktop.DBus")
+        .and_then(|rule| rule.interface("org.freedesktop.DBus"))
+        .and_then(|rule| rule.member("NameOwnerChanged"))
+        .and_then(|rule| rule.arg0ns(DBUS_MPRIS_INTERFACE_NAME))
+        .map_err(failed)?
+        .build();
+
+    let stream = MessageStream::for_match_rule(rule, &connection, None)
+        .await
+        .map_err(failed)?;
+
+    Ok(stream
+        .filter_map(|message| future::ready(message.ok().and_then(NameOwnerChanged::from_message))))
+}
+
 /// Creates a proxy for "org.freedesktop.DBus.Properties".
-pub async fn create_properties_proxy(
+pub(crate) async fn create_properties_proxy(
     shared_connection: Arc<Mutex<Connection>>,
     bus: &str,
-) -> MprisResult<Proxy<'static>> {
+) -> MprisResult<PropertiesProxy<'static>> {
     let connection = shared_connection
         .try_lock()
         .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
 
-    let properties_proxy = Proxy::new(
-        &*connection,
-        bus.to_string(),
-        DBUS_MPRIS_INTERFACE_PATH,
-        "org.freedesktop.DBus.Properties",
-    )
-    .await
-    .map_err(|_| ProxyError::failed_to_create("org.freedesktop.DBus.Properties"))?;
+    let properties_proxy = PropertiesProxy::builder(&connection)
+        .destination(bus.to_string())
+        .map_err(|err| ProxyError::other(bus, err))?
+        .path(DBUS_MPRIS_INTERFACE_PATH)
+        .map_err(|err| ProxyError::other(bus, err))?
+        .build()
+        .await
+        .map_err(|_| ProxyError::failed_to_create(bus, "org.freedesktop.DBus.Properties"))?;
 
     Ok(properties_proxy)
 }
 
-/// Proxy for "org.mpris.MediaPlayer2.Player" interface.
-pub async fn create_player_proxy(
+/// Proxy for "org.freedesktop.DBus.Introspectable" interface.
+pub(crate) async fn create_introspectable_proxy(
     shared_connection: Arc<Mutex<Connection>>,
     bus: &str,
-) -> MprisResult<Proxy<'static>> {
+) -> MprisResult<IntrospectableProxy<'static>> {
     let connection = shared_connection
         .try_lock()
         .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
 
-    let proxy: Proxy = zbus::proxy::Builder::new(&*connection)
+    let introspectable_proxy = IntrospectableProxy::builder(&connection)
         .destination(bus.to_string())
-        .map_err(|err| ProxyError::other(err))?
+        .map_err(|err| ProxyError::other(bus, err))?
         .path(DBUS_MPRIS_INTERFACE_PATH)
-        .map_err(|err| ProxyError::other(err))?
-        .interface(format!("{DBUS_MPRIS_INTERFACE_NAME}.Player"))
-        .map_err(|err| ProxyError::other(err))?
-        .cache_properties(zbus::proxy::CacheProperties::No)
+        .map_err(|err| ProxyError::other(bus, err))?
+        .build()
+        .await
+        .map_err(|_| ProxyError::failed_to_create(bus, "org.freedesktop.DBus.Introspectable"))?;
+
+    Ok(introspectable_proxy)
+}
+
+/// Proxy for "org.mpris.MediaPlayer2" interface.
+pub(crate) async fn create_root_proxy(
+    shared_connection: Arc<Mutex<Connection>>,
+    bus: &str,
+) -> MprisResult<MediaPlayer2Proxy<'static>> {
+    let connection = shared_connection
+        .try_lock()
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
+
+    let proxy = MediaPlayer2Proxy::builder(&connection)
+        .destination(bus.to_string())
+        .map_err(|err| ProxyError::other(bus, err))?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+        .map_err(|_| ProxyError::failed_to_create(bus, DBUS_MPRIS_INTERFACE_NAME))?;
+
+    Ok(proxy)
+}
+
+/// Proxy for "org.mpris.MediaPlayer2.Player" interface.
+pub(crate) async fn create_player_proxy(
+    shared_connection: Arc<Mutex<Connection>>,
+    bus: &str,
+) -> MprisResult<PlayerProxy<'static>> {
+    let connection = shared_connection
+        .try_lock()
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
+
+    let proxy = PlayerProxy::builder(&connection)
+        .destination(bus.to_string())
+        .map_err(|err| ProxyError::other(bus, err))?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+        .map_err(|_| {
+            ProxyError::failed_to_create(bus, format!("{DBUS_MPRIS_INTERFACE_NAME}.Player"))
+        })?;
+
+    Ok(proxy)
+}
+
+/// Proxy for "org.mpris.MediaPlayer2.TrackList" interface.
+pub(crate) async fn create_track_list_proxy(
+    shared_connection: Arc<Mutex<Connection>>,
+    bus: &str,
+) -> MprisResult<TrackListProxy<'static>> {
+    let connection = shared_connection
+        .try_lock()
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
+
+    let proxy = TrackListProxy::builder(&connection)
+        .destination(bus.to_string())
+        .map_err(|err| ProxyError::other(bus, err))?
+        .cache_properties(CacheProperties::No)
+        .build()
+        .await
+        .map_err(|_| ProxyError::failed_to_create(bus, DBUS_MPRIS_TRACK_LIST_INTERFACE_NAME))?;
+
+    Ok(proxy)
+}
+
+/// Proxy for "org.mpris.MediaPlayer2.Playlists" interface.
+pub(crate) async fn create_playlists_proxy(
+    shared_connection: Arc<Mutex<Connection>>,
+    bus: &str,
+) -> MprisResult<PlaylistsProxy<'static>> {
+    let connection = shared_connection
+        .try_lock()
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))?;
+
+    let proxy = PlaylistsProxy::builder(&connection)
+        .destination(bus.to_string())
+        .map_err(|err| ProxyError::other(bus, err))?
+        .cache_properties(CacheProperties::No)
         .build()
         .await
-        .map_err(|_| ProxyError::failed_to_create(format!("{DBUS_MPRIS_INTERFACE_NAME}.Player")))?;
+        .map_err(|_| ProxyError::failed_to_create(bus, DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME))?;
 
     Ok(proxy)
 }
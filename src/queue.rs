@@ -0,0 +1,207 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::Notify;
+
+use crate::{
+    EventCapacity, EventEnvelope, MprisResult, OverflowPolicy, PlayerIdentity, stats::ReceiverGone,
+};
+
+/// The event channel of a [`crate::Mpris`], bounded by [`crate::MprisOptions::with_event_capacity`].
+///
+/// Unlike a tokio channel, the bound can change while the watch tasks run,
+/// and the oldest queued event can be dropped from the sending side.
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    state: Mutex<QueueState>,
+
+    /// Wakes up the receiver when an event got queued.
+    queued: Notify,
+
+    /// Wakes up the blocked senders when an event got taken out, or the receiver went away.
+    taken: Notify,
+}
+
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<MprisResult<EventEnvelope>>,
+    capacity: Option<EventCapacity>,
+    closed: bool,
+
+    /// The next sequence number of each player.
+    sequences: HashMap<PlayerIdentity, u64>,
+}
+
+impl QueueState {
+    /// Numbers the event in the order it gets queued or dropped, for its player.
+    fn number(&mut self, result: &mut MprisResult<EventEnvelope>) {
+        if let Ok(envelope) = result {
+            let sequence = self
+                .sequences
+                .entry(envelope.event().identity().clone())
+                .or_default();
+
+            envelope.set_sequence(*sequence);
+            *sequence += 1;
+        }
+    }
+}
+
+impl EventQueue {
+    /// Creates a queue, handing out its receiving half.
+    pub(crate) fn new() -> (Arc<Self>, EventReceiver) {
+        let queue = Arc::new(Self::default());
+        (Arc::clone(&queue), EventReceiver(queue))
+    }
+
+    /// Changes the bound, applied to the events sent out next.
+    pub(crate) fn set_capacity(&self, capacity: Option<EventCapacity>) {
+        self.lock().capacity = capacity;
+        self.taken.notify_waiters();
+    }
+
+    /// Queues an event, following the overflow policy when full.
+    ///
+    /// Errors once the receiver went away, the blocked senders included, dropping the event.
+    ///
+    /// The events get their sequence number here, so the numbers follow the queue order
+    /// and the dropped events leave a gap.
+    pub(crate) async fn send(
+        &self,
+        mut result: MprisResult<EventEnvelope>,
+    ) -> Result<(), ReceiverGone> {
+        loop {
+            // Registered before checking, so a take in between isn't missed.
+            let taken = self.taken.notified();
+
+            {
+                let mut state = self.lock();
+                if state.closed {
+                    return Err(ReceiverGone);
+                }
+
+                match state.capacity {
+                    Some(bound) if state.events.len() >= bound.capacity() => match bound.policy() {
+                        OverflowPolicy::DropNewest => {
+                            state.number(&mut result);
+                            return Ok(());
+                        }
+                        OverflowPolicy::DropOldest => {
+                            let excess = state.events.len() + 1 - bound.capacity();
+                            state.events.drain(..excess);
+                        }
+                        OverflowPolicy::Block => {}
+                    },
+                    _ => {}
+                }
+
+                let full = state
+                    .capacity
+                    .is_some_and(|bound| state.events.len() >= bound.capacity());
+
+                if !full {
+                    state.number(&mut result);
+                    state.events.push_back(result);
+                    self.queued.notify_one();
+                    return Ok(());
+                }
+            }
+
+            taken.await;
+        }
+    }
+
+    /// Waits for the receiver to go away.
+    pub(crate) async fn closed(&self) {
+        loop {
+            let taken = self.taken.notified();
+            if self.lock().closed {
+                return;
+            }
+
+            taken.await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl fmt::Debug for EventQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+
+        f.debug_struct("EventQueue")
+            .field("queued", &state.events.len())
+            .field("capacity", &state.capacity)
+            .field("closed", &state.closed)
+            .finish()
+    }
+}
+
+/// The receiving half of an [`EventQueue`], closing it when dropped.
+#[derive(Debug)]
+pub(crate) struct EventReceiver(Arc<EventQueue>);
+
+impl EventReceiver {
+    /// Waits for the next event.
+    pub(crate) async fn recv(&mut self) -> MprisResult<EventEnvelope> {
+        let queue = Arc::clone(&self.0);
+
+        loop {
+            let queued = queue.queued.notified();
+
+            if let Some(result) = self.try_recv() {
+                return result;
+            }
+
+            queued.await;
+        }
+    }
+
+    /// Waits for an event, then takes up to the limit of the queued ones, mapped into the buffer.
+    pub(crate) async fn recv_many<T>(
+        &mut self,
+        buffer: &mut Vec<T>,
+        limit: usize,
+        map: impl Fn(MprisResult<EventEnvelope>) -> T,
+    ) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+
+        let first = self.recv().await;
+        buffer.push(map(first));
+
+        let mut state = self.0.lock();
+        let taken = (limit - 1).min(state.events.len());
+        buffer.extend(state.events.drain(..taken).map(map));
+        drop(state);
+
+        self.0.taken.notify_waiters();
+        taken + 1
+    }
+
+    /// Takes the next event, if there's one queued.
+    pub(crate) fn try_recv(&mut self) -> Option<MprisResult<EventEnvelope>> {
+        let result = self.0.lock().events.pop_front()?;
+        self.0.taken.notify_waiters();
+
+        Some(result)
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.closed = true;
+        state.events.clear();
+        drop(state);
+
+        self.0.taken.notify_waiters();
+    }
+}
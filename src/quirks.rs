@@ -0,0 +1,21 @@
+/// The known misbehaviors of a [`crate::MprisPlayer`], learned while talking to it.
+///
+/// mprizzle works around these on its own, they are exposed for debugging
+/// and for reporting the players that need fixing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerQuirks {
+    /// `Properties.GetAll` fails or leaves out mandatory properties.
+    broken_get_all: bool,
+}
+
+impl PlayerQuirks {
+    /// Returns `true` if `Properties.GetAll` can't be relied on,
+    /// making the properties get fetched one by one instead.
+    pub fn broken_get_all(&self) -> bool {
+        self.broken_get_all
+    }
+
+    pub(crate) fn set_broken_get_all(&mut self) {
+        self.broken_get_all = true;
+    }
+}
@@ -0,0 +1,439 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use zbus::Connection;
+use zvariant::{
+    LE, OwnedObjectPath, OwnedValue, Value,
+    serialized::{Context, Data},
+};
+
+use crate::{
+    Capabilities, EventEnvelope, MetadataDiff, MprisError, MprisEvent, MprisPlayer, MprisResult,
+    PlaybackStatus, PlayerIdentity, PlayerMetadata, PlayerSnapshot, Playlist, PropertiesDiff,
+    stats::Stats,
+};
+
+/// Records the events of a [`crate::Mpris`] to a file, to be replayed later with [`crate::Mpris::replay`].
+///
+/// Each event goes on its own JSON line, along with when it happened. The values coming
+/// from the players are kept as their D-Bus encoding, so they replay exactly as they were.
+/// The errors aren't recorded.
+///
+/// # Example
+///
+/// ```no_run
+/// use mprizzle::{EventRecorder, Mpris};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut mpris = Mpris::new().await?;
+///     let mut recorder = EventRecorder::create("events.jsonl")?;
+///
+///     mpris.watch();
+///
+///     loop {
+///         match mpris.recv_envelope().await? {
+///             Ok(envelope) => recorder.record(&envelope)?,
+///             // A player failing doesn't stop the recording.
+///             Err(err) => eprintln!("{err}"),
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EventRecorder<W: Write = BufWriter<File>> {
+    writer: W,
+}
+
+impl EventRecorder {
+    /// Creates the file to record to, replacing the one already there.
+    pub fn create(path: impl AsRef<Path>) -> MprisResult<Self> {
+        let file = File::create(path).map_err(|err| MprisError::FailedToRecord(err.to_string()))?;
+
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Records to the writer.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Records the event, written out right away so a crash doesn't lose it.
+    pub fn record(&mut self, envelope: &EventEnvelope) -> MprisResult<()> {
+        let record = Record::new(envelope);
+        let line = serde_json::to_string(&record)
+            .map_err(|err| MprisError::FailedToRecord(err.to_string()))?;
+
+        writeln!(self.writer, "{line}")
+            .and_then(|_| self.writer.flush())
+            .map_err(|err| MprisError::FailedToRecord(err.to_string()))
+    }
+
+    /// Takes the writer back.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads the events recorded by an [`EventRecorder`].
+pub(crate) fn read_records(path: impl AsRef<Path>) -> MprisResult<Vec<Record>> {
+    let file = File::open(path).map_err(|err| MprisError::FailedToReplay(err.to_string()))?;
+
+    BufReader::new(file)
+        .lines()
+        .filter(|line| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|line| {
+            let line = line.map_err(|err| MprisError::FailedToReplay(err.to_string()))?;
+            serde_json::from_str(&line).map_err(|err| MprisError::FailedToReplay(err.to_string()))
+        })
+        .collect()
+}
+
+/// A recorded event, as a line of the recording.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Record {
+    /// When the event happened, in milliseconds since the unix epoch.
+    at: u64,
+
+    /// The bus of the player.
+    bus: String,
+
+    #[serde(flatten)]
+    event: RecordedEvent,
+}
+
+impl Record {
+    fn new(envelope: &EventEnvelope) -> Self {
+        let at = envelope
+            .received_at()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        Self {
+            at,
+            bus: envelope.event().identity().bus().to_string(),
+            event: RecordedEvent::new(envelope.event()),
+        }
+    }
+
+    /// Gets when the event happened.
+    pub(crate) fn at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.at)
+    }
+
+    /// Turns the record back into the event, creating the player for the attach events.
+    pub(crate) async fn into_event(
+        self,
+        connection: Arc<Mutex<Connection>>,
+        stats: Stats,
+    ) -> MprisResult<MprisEvent> {
+        let identity = PlayerIdentity::new(self.bus)?;
+
+        let event = match self.event {
+            RecordedEvent::Attached {
+                playback_status,
+                metadata,
+                volume,
+            } => {
+                let player = MprisPlayer::new(connection, identity)
+                    .await?
+                    .with_stats(stats);
+                let snapshot = PlayerSnapshot::recorded(
+                    playback_status.and_then(|status| PlaybackStatus::from_str(&status).ok()),
+                    metadata.map(|metadata| metadata_of(&metadata)),
+                    volume,
+                );
+
+                MprisEvent::PlayerAttached(player, snapshot)
+            }
+            RecordedEvent::Detached => MprisEvent::PlayerDetached(identity),
+            RecordedEvent::PropertiesChanged(diff) => {
+                MprisEvent::PlayerPropertiesChanged(identity, diff.into_diff())
+            }
+            RecordedEvent::RootPropertiesChanged(diff) => {
+                MprisEvent::PlayerRootPropertiesChanged(identity, diff.into_diff())
+            }
+            RecordedEvent::MetadataChanged { old, new } => MprisEvent::PlayerMetadataChanged(
+                identity,
+                MetadataDiff::new(old.map(|old| metadata_of(&old)), metadata_of(&new)),
+            ),
+            RecordedEvent::TrackChanged { metadata } => {
+                MprisEvent::PlayerTrackChanged(identity, metadata_of(&metadata))
+            }
+            RecordedEvent::TrackStarted { metadata } => {
+                MprisEvent::PlayerTrackStarted(identity, metadata_of(&metadata))
+            }
+            RecordedEvent::TrackFinished { metadata } => {
+                MprisEvent::PlayerTrackFinished(identity, metadata_of(&metadata))
+            }
+            RecordedEvent::TrackScrobblable { metadata } => {
+                MprisEvent::PlayerTrackScrobblable(identity, metadata_of(&metadata))
+            }
+            RecordedEvent::TrackEnding { remaining } => {
+                MprisEvent::PlayerTrackEnding(identity, remaining)
+            }
+            RecordedEvent::PlaybackStatusChanged { old, new } => {
+                MprisEvent::PlayerPlaybackStatusChanged(
+                    identity,
+                    PlaybackStatus::from_str(&old)?,
+                    PlaybackStatus::from_str(&new)?,
+                )
+            }
+            RecordedEvent::CapabilitiesChanged { capabilities } => {
+                let properties = capabilities
+                    .into_iter()
+                    .map(|(property, can)| (property, OwnedValue::from(can)))
+                    .collect();
+
+                MprisEvent::PlayerCapabilitiesChanged(identity, Capabilities::new(&properties))
+            }
+            RecordedEvent::PlaylistChanged { id, name, icon } => {
+                let id = OwnedObjectPath::try_from(id)
+                    .map_err(|err| MprisError::FailedToReplay(err.to_string()))?;
+
+                MprisEvent::PlayerPlaylistChanged(
+                    identity,
+                    Playlist::from((id, name, icon.unwrap_or_default())),
+                )
+            }
+            RecordedEvent::Seeked => MprisEvent::PlayerSeeked(identity),
+            RecordedEvent::Position { position } => MprisEvent::PlayerPosition(identity, position),
+            RecordedEvent::AutoPaused { playing } => {
+                MprisEvent::PlayerAutoPaused(identity, PlayerIdentity::new(playing)?)
+            }
+        };
+
+        Ok(event)
+    }
+}
+
+/// The data of a recorded event, tagged with its kind.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum RecordedEvent {
+    Attached {
+        playback_status: Option<String>,
+        metadata: Option<RecordedValues>,
+        volume: Option<f64>,
+    },
+    Detached,
+    PropertiesChanged(RecordedDiff),
+    RootPropertiesChanged(RecordedDiff),
+    MetadataChanged {
+        old: Option<RecordedValues>,
+        new: RecordedValues,
+    },
+    TrackChanged {
+        metadata: RecordedValues,
+    },
+    TrackStarted {
+        metadata: RecordedValues,
+    },
+    TrackFinished {
+        metadata: RecordedValues,
+    },
+    TrackScrobblable {
+        metadata: RecordedValues,
+    },
+    TrackEnding {
+        remaining: Duration,
+    },
+    PlaybackStatusChanged {
+        old: String,
+        new: String,
+    },
+    CapabilitiesChanged {
+        capabilities: BTreeMap<String, bool>,
+    },
+    PlaylistChanged {
+        id: String,
+        name: String,
+        icon: Option<String>,
+    },
+    Seeked,
+    Position {
+        position: Duration,
+    },
+    AutoPaused {
+        playing: String,
+    },
+}
+
+impl RecordedEvent {
+    fn new(event: &MprisEvent) -> Self {
+        match event {
+            MprisEvent::PlayerAttached(_, snapshot) => RecordedEvent::Attached {
+                playback_status: snapshot.playback_status().map(|status| status.to_string()),
+                metadata: snapshot.metadata().map(values_of),
+                volume: snapshot.volume(),
+            },
+            MprisEvent::PlayerDetached(_) => RecordedEvent::Detached,
+            MprisEvent::PlayerPropertiesChanged(_, diff) => {
+                RecordedEvent::PropertiesChanged(RecordedDiff::new(diff))
+            }
+            MprisEvent::PlayerRootPropertiesChanged(_, diff) => {
+                RecordedEvent::RootPropertiesChanged(RecordedDiff::new(diff))
+            }
+            MprisEvent::PlayerMetadataChanged(_, diff) => RecordedEvent::MetadataChanged {
+                old: diff.old_metadata().map(values_of),
+                new: values_of(diff.new_metadata()),
+            },
+            MprisEvent::PlayerTrackChanged(_, metadata) => RecordedEvent::TrackChanged {
+                metadata: values_of(metadata),
+            },
+            MprisEvent::PlayerTrackStarted(_, metadata) => RecordedEvent::TrackStarted {
+                metadata: values_of(metadata),
+            },
+            MprisEvent::PlayerTrackFinished(_, metadata) => RecordedEvent::TrackFinished {
+                metadata: values_of(metadata),
+            },
+            MprisEvent::PlayerTrackScrobblable(_, metadata) => RecordedEvent::TrackScrobblable {
+                metadata: values_of(metadata),
+            },
+            MprisEvent::PlayerTrackEnding(_, remaining) => RecordedEvent::TrackEnding {
+                remaining: *remaining,
+            },
+            MprisEvent::PlayerPlaybackStatusChanged(_, old, new) => {
+                RecordedEvent::PlaybackStatusChanged {
+                    old: old.to_string(),
+                    new: new.to_string(),
+                }
+            }
+            MprisEvent::PlayerCapabilitiesChanged(_, capabilities) => {
+                RecordedEvent::CapabilitiesChanged {
+                    capabilities: BTreeMap::from([
+                        ("CanPlay".to_string(), capabilities.can_play()),
+                        ("CanPause".to_string(), capabilities.can_pause()),
+                        ("CanSeek".to_string(), capabilities.can_seek()),
+                        ("CanGoNext".to_string(), capabilities.can_go_next()),
+                        ("CanGoPrevious".to_string(), capabilities.can_go_previous()),
+                        ("CanControl".to_string(), capabilities.can_control()),
+                    ]),
+                }
+            }
+            MprisEvent::PlayerPlaylistChanged(_, playlist) => RecordedEvent::PlaylistChanged {
+                id: playlist.id().to_string(),
+                name: playlist.name().to_string(),
+                icon: playlist.icon().map(String::from),
+            },
+            MprisEvent::PlayerSeeked(_) => RecordedEvent::Seeked,
+            MprisEvent::PlayerPosition(_, position) => RecordedEvent::Position {
+                position: *position,
+            },
+            MprisEvent::PlayerAutoPaused(_, playing) => RecordedEvent::AutoPaused {
+                playing: playing.bus().to_string(),
+            },
+        }
+    }
+}
+
+/// A recorded [`PropertiesDiff`].
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedDiff {
+    interface: String,
+    changed: RecordedValues,
+    invalidated: Vec<String>,
+    refetched: RecordedValues,
+}
+
+impl RecordedDiff {
+    fn new(diff: &PropertiesDiff) -> Self {
+        Self {
+            interface: diff.interface().to_string(),
+            changed: recorded_values(diff.changed()),
+            invalidated: diff.invalidated().to_vec(),
+            refetched: recorded_values(diff.refetched()),
+        }
+    }
+
+    fn into_diff(self) -> PropertiesDiff {
+        let mut diff = PropertiesDiff::new(
+            &self.interface,
+            owned_values(&self.changed),
+            self.invalidated,
+        );
+
+        for (property, value) in owned_values(&self.refetched) {
+            diff.insert(&property, value);
+        }
+
+        diff
+    }
+}
+
+/// Named values, kept as their D-Bus encoding.
+type RecordedValues = BTreeMap<String, RecordedValue>;
+
+/// A value encoded as a D-Bus variant, written out in hex.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+struct RecordedValue(String);
+
+impl RecordedValue {
+    /// Encodes the value, None for the ones that can't be, like file descriptors.
+    fn new(value: &Value) -> Option<Self> {
+        let data = zvariant::to_bytes(Context::new_dbus(LE, 0), value).ok()?;
+        let hex = data.bytes().iter().fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        });
+
+        Some(Self(hex))
+    }
+
+    /// Decodes the value, None if the recording got mangled.
+    fn value(&self) -> Option<OwnedValue> {
+        let bytes = (0..self.0.len())
+            .step_by(2)
+            .map(|at| u8::from_str_radix(self.0.get(at..at + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        let data = Data::new(bytes, Context::new_dbus(LE, 0));
+        data.deserialize::<OwnedValue>()
+            .ok()
+            .map(|(value, _)| value)
+    }
+}
+
+fn recorded_values(values: &HashMap<String, OwnedValue>) -> RecordedValues {
+    values
+        .iter()
+        .filter_map(|(name, value)| Some((name.clone(), RecordedValue::new(value)?)))
+        .collect()
+}
+
+fn owned_values(values: &RecordedValues) -> HashMap<String, OwnedValue> {
+    values
+        .iter()
+        .filter_map(|(name, value)| Some((name.clone(), value.value()?)))
+        .collect()
+}
+
+fn values_of(metadata: &PlayerMetadata) -> RecordedValues {
+    metadata
+        .fields()
+        .filter_map(|field| Some((field.to_string(), RecordedValue::new(metadata.get(field)?)?)))
+        .collect()
+}
+
+fn metadata_of(values: &RecordedValues) -> PlayerMetadata<'static> {
+    PlayerMetadata::new(
+        owned_values(values)
+            .into_iter()
+            .map(|(field, value)| (field, value.into()))
+            .collect(),
+    )
+}
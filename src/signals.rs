@@ -0,0 +1,262 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex as StdMutex},
+};
+
+use futures::{
+    StreamExt, future,
+    stream::{self, BoxStream},
+};
+use tokio::sync::{Mutex, mpsc};
+use tokio_util::sync::CancellationToken;
+use zbus::{
+    Connection, MatchRule, Message, MessageStream,
+    fdo::{DBusProxy, PropertiesChanged},
+    message::Type,
+};
+
+use crate::{
+    EventMask, MprisError, MprisResult,
+    proxies::{
+        self, DBUS_MPRIS_INTERFACE_PATH, DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+        DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME, PlaylistChanged, Seeked,
+    },
+};
+
+/// A signal sent by a player, parsed once for whoever receives it.
+#[derive(Debug, Clone)]
+pub(crate) enum PlayerSignal {
+    PropertiesChanged(PropertiesChanged),
+    Seeked(Seeked),
+    PlaylistChanged(PlaylistChanged),
+}
+
+impl PlayerSignal {
+    /// Parses the signal, None if it's not one the player tasks listen to.
+    fn from_message(message: Message) -> Option<Self> {
+        let member = message.header().member()?.to_string();
+
+        match member.as_str() {
+            "PropertiesChanged" => {
+                PropertiesChanged::from_message(message).map(Self::PropertiesChanged)
+            }
+            "Seeked" => Seeked::from_message(message).map(Self::Seeked),
+            "PlaylistChanged" => PlaylistChanged::from_message(message).map(Self::PlaylistChanged),
+            _ => None,
+        }
+    }
+
+    fn message(&self) -> &Message {
+        match self {
+            PlayerSignal::PropertiesChanged(signal) => signal.message(),
+            PlayerSignal::Seeked(signal) => signal.message(),
+            PlayerSignal::PlaylistChanged(signal) => signal.message(),
+        }
+    }
+}
+
+/// The signals of a single player.
+pub(crate) type PlayerSignals = BoxStream<'static, PlayerSignal>;
+
+/// Where a player task gets the signals of its player from.
+#[derive(Debug, Clone)]
+pub(crate) enum SignalSource {
+    /// Subscribes to the signals of the player on its own, for the players watched alone.
+    Own,
+
+    /// Gets them routed by the dispatcher of its connection.
+    Shared(SignalDispatcher),
+}
+
+impl SignalSource {
+    /// Gets the signals of the player the mask needs, None if it needs none.
+    pub(crate) async fn signals(
+        &self,
+        shared_connection: Arc<Mutex<Connection>>,
+        bus: &str,
+        mask: EventMask,
+    ) -> MprisResult<Option<PlayerSignals>> {
+        if !needs_signals(mask) {
+            return Ok(None);
+        }
+
+        let signals = match self {
+            SignalSource::Own => {
+                let connection = lock_connection(&shared_connection)?;
+                subscribe(&connection, mask, Some(bus)).await?.boxed()
+            }
+            SignalSource::Shared(dispatcher) => dispatcher.register(bus).await,
+        };
+
+        Ok(Some(signals))
+    }
+}
+
+/// The players a dispatcher routes the signals to, by the unique name they send them from.
+type Routes = HashMap<String, Vec<mpsc::UnboundedSender<PlayerSignal>>>;
+
+/// Routes the signals of every player on a connection to the tasks of those players.
+///
+/// Subscribes once per signal the mask needs, whatever the number of players,
+/// rather than each player task adding its own match rules.
+#[derive(Debug, Clone)]
+pub(crate) struct SignalDispatcher {
+    routes: Arc<StdMutex<Routes>>,
+    dbus_proxy: DBusProxy<'static>,
+}
+
+impl SignalDispatcher {
+    /// Subscribes to the signals on the connection, giving the task routing them.
+    ///
+    /// The task stops once the connection closes or the token gets cancelled.
+    pub(crate) async fn new(
+        shared_connection: Arc<Mutex<Connection>>,
+        mask: EventMask,
+        cancel: CancellationToken,
+    ) -> MprisResult<(Self, impl Future<Output = MprisResult<()>> + Send + 'static)> {
+        let dbus_proxy = proxies::create_dbus_proxy(Arc::clone(&shared_connection)).await?;
+        let connection = lock_connection(&shared_connection)?;
+
+        let mut signals = if needs_signals(mask) {
+            subscribe(&connection, mask, None).await?.boxed()
+        } else {
+            stream::empty().boxed()
+        };
+
+        let dispatcher = Self {
+            routes: Arc::default(),
+            dbus_proxy,
+        };
+
+        let routes = Arc::clone(&dispatcher.routes);
+        let task = async move {
+            loop {
+                let signal = tokio::select! {
+                    biased;
+
+                    _ = cancel.cancelled() => return Ok(()),
+
+                    signal = signals.next() => match signal {
+                        Some(signal) => signal,
+                        None => return Ok(()),
+                    },
+                };
+
+                let Some(sender) = signal
+                    .message()
+                    .header()
+                    .sender()
+                    .map(|sender| sender.to_string())
+                else {
+                    continue;
+                };
+
+                // The player tasks that stopped get dropped along the way.
+                let mut routes = routes.lock().unwrap_or_else(|err| err.into_inner());
+                if let Some(players) = routes.get_mut(&sender) {
+                    players.retain(|player| player.send(signal.clone()).is_ok());
+
+                    if players.is_empty() {
+                        routes.remove(&sender);
+                    }
+                }
+            }
+        };
+
+        Ok((dispatcher, task))
+    }
+
+    /// Routes the signals sent by the current owner of the bus to the returned stream.
+    ///
+    /// The stream ends right away if the bus has no owner, the player went away then.
+    async fn register(&self, bus: &str) -> PlayerSignals {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+
+        let owner = match bus.try_into() {
+            Ok(bus) => self.dbus_proxy.get_name_owner(bus).await.ok(),
+            Err(_) => None,
+        };
+
+        if let Some(owner) = owner {
+            let mut routes = self.routes.lock().unwrap_or_else(|err| err.into_inner());
+
+            // The owners that went away don't send anything anymore, for the routes to be dropped.
+            routes.retain(|_, players| {
+                players.retain(|player| !player.is_closed());
+                !players.is_empty()
+            });
+
+            routes.entry(owner.to_string()).or_default().push(sender);
+        }
+
+        stream::poll_fn(move |cx| receiver.poll_recv(cx)).boxed()
+    }
+}
+
+/// Gets a handle on the shared connection, without holding onto the lock.
+fn lock_connection(shared_connection: &Mutex<Connection>) -> MprisResult<Connection> {
+    shared_connection
+        .try_lock()
+        .map(|connection| connection.clone())
+        .map_err(|err| MprisError::FailedToLockSharedConnection(err.to_string()))
+}
+
+/// Returns `true` if the mask needs any of the signals.
+fn needs_signals(mask: EventMask) -> bool {
+    mask.needs_properties() || mask.needs_seeked() || mask.needs_playlists()
+}
+
+/// Subscribes to the signals the mask needs, of every player or of the one on the bus.
+async fn subscribe(
+    connection: &Connection,
+    mask: EventMask,
+    bus: Option<&str>,
+) -> MprisResult<impl futures::Stream<Item = PlayerSignal> + Send + 'static> {
+    let wanted = [
+        (
+            mask.needs_properties(),
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        ),
+        (
+            mask.needs_seeked(),
+            DBUS_MPRIS_PLAYER_INTERFACE_NAME,
+            "Seeked",
+        ),
+        (
+            mask.needs_playlists(),
+            DBUS_MPRIS_PLAYLISTS_INTERFACE_NAME,
+            "PlaylistChanged",
+        ),
+    ];
+
+    let mut streams = Vec::new();
+
+    for (_, interface, member) in wanted.into_iter().filter(|(needed, _, _)| *needed) {
+        let failed = |err: zbus::Error| {
+            MprisError::Other(format!(
+                "Failed to create a signal stream for {member}: {err}"
+            ))
+        };
+
+        let mut rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .path(DBUS_MPRIS_INTERFACE_PATH)
+            .and_then(|rule| rule.interface(interface))
+            .and_then(|rule| rule.member(member))
+            .map_err(failed)?;
+
+        if let Some(bus) = bus {
+            rule = rule.sender(bus).map_err(failed)?;
+        }
+
+        let stream = MessageStream::for_match_rule(rule.build(), connection, None)
+            .await
+            .map_err(failed)?;
+
+        streams.push(stream);
+    }
+
+    Ok(stream::select_all(streams)
+        .filter_map(|message| future::ready(message.ok().and_then(PlayerSignal::from_message))))
+}
@@ -0,0 +1,124 @@
+use std::{collections::HashMap, time::Duration};
+
+use futures::future;
+use tokio::{
+    sync::{broadcast, broadcast::error::RecvError, watch},
+    time::Instant,
+};
+
+use crate::{
+    EventMask, MprisEvent, MprisOptions, MprisPlayer, MprisResult, PlaybackStatus, PlayerIdentity,
+    SharedPlayers, stats::EventSender,
+};
+
+/// How long a player paused by the policy can't pause the others by starting to play.
+///
+/// Its status change might have been sent before the pause, or it might be fighting it.
+const SINGLE_PLAYBACK_GRACE: Duration = Duration::from_secs(1);
+
+/// Pauses the other players each time one starts playing, while the options say so.
+///
+/// Only subscribes to the events while turned on, so it costs nothing otherwise.
+pub(crate) async fn enforce(
+    subscribers: broadcast::Sender<MprisResult<MprisEvent>>,
+    registry: SharedPlayers,
+    mut options: watch::Receiver<MprisOptions>,
+    event_sender: EventSender,
+    mask: EventMask,
+) -> MprisResult<()> {
+    // The players paused by the policy, and when.
+    let mut paused: HashMap<PlayerIdentity, Instant> = HashMap::new();
+
+    loop {
+        tokio::select! {
+            biased;
+
+            _ = event_sender.closed() => return Ok(()),
+
+            turned_on = options.wait_for(MprisOptions::single_playback) => {
+                if turned_on.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut events = subscribers.subscribe();
+
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = event_sender.closed() => return Ok(()),
+
+                changed = options.changed() => {
+                    if changed.is_err() {
+                        return Ok(());
+                    }
+
+                    if !options.borrow().single_playback() {
+                        break;
+                    }
+                }
+
+                event = events.recv() => {
+                    let playing = match event {
+                        Ok(Ok(MprisEvent::PlayerPlaybackStatusChanged(identity, _, PlaybackStatus::Playing))) => identity,
+                        Err(RecvError::Closed) => return Ok(()),
+                        _ => continue,
+                    };
+
+                    paused.retain(|_, at| at.elapsed() < SINGLE_PLAYBACK_GRACE);
+                    if paused.contains_key(&playing) {
+                        continue;
+                    }
+
+                    for result in pause_others(&registry, &playing).await {
+                        let event = result.map(|identity| {
+                            paused.insert(identity.clone(), Instant::now());
+                            MprisEvent::PlayerAutoPaused(identity, playing.clone())
+                        });
+
+                        if event.is_ok() && !mask.contains(EventMask::AUTO_PAUSED) {
+                            continue;
+                        }
+
+                        // A send only fails once the receiver went away, which stops the task.
+                        if event_sender.send(event).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pauses every other player that plays, concurrently.
+///
+/// Gives the identities of the players paused, and the errors of the ones that couldn't be.
+async fn pause_others(
+    registry: &SharedPlayers,
+    playing: &PlayerIdentity,
+) -> Vec<MprisResult<PlayerIdentity>> {
+    // Copied out, so the registry isn't locked while the players answer.
+    let others: Vec<MprisPlayer> = registry
+        .lock()
+        .await
+        .iter()
+        .filter(|(identity, _)| *identity != playing)
+        .map(|(_, player)| MprisPlayer::clone(player))
+        .collect();
+
+    let results = future::join_all(others.into_iter().map(|player| async move {
+        match player.playback_status().await {
+            Ok(PlaybackStatus::Playing) => {
+                Some(player.pause().await.map(|_| player.identity().clone()))
+            }
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }))
+    .await;
+
+    results.into_iter().flatten().collect()
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use zvariant::OwnedValue;
+
+use crate::{Capabilities, LoopStatus, PlaybackStatus, PlayerMetadata, player::owned_metadata};
+
+/// The state of a player at a point in time, as carried by [`crate::MprisEvent::PlayerAttached`].
+///
+/// Got with a single `GetAll`, the properties the player left out or failed to give are None.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerSnapshot {
+    playback_status: Option<PlaybackStatus>,
+    metadata: Option<PlayerMetadata<'static>>,
+    volume: Option<f64>,
+}
+
+impl PlayerSnapshot {
+    /// Picks the state out of the player properties.
+    pub(crate) fn new(properties: HashMap<String, OwnedValue>) -> Self {
+        Self::from_state(&PlayerState::new(properties))
+    }
+
+    /// Picks the snapshot out of the whole state.
+    pub(crate) fn from_state(state: &PlayerState) -> Self {
+        Self {
+            playback_status: state.playback_status.clone(),
+            metadata: state.metadata.clone(),
+            volume: state.volume,
+        }
+    }
+
+    /// Puts back together a recorded snapshot.
+    #[cfg(feature = "recording")]
+    pub(crate) fn recorded(
+        playback_status: Option<PlaybackStatus>,
+        metadata: Option<PlayerMetadata<'static>>,
+        volume: Option<f64>,
+    ) -> Self {
+        Self {
+            playback_status,
+            metadata,
+            volume,
+        }
+    }
+
+    /// Gets the playback status.
+    pub fn playback_status(&self) -> Option<&PlaybackStatus> {
+        self.playback_status.as_ref()
+    }
+
+    /// Gets the metadata of the current track.
+    pub fn metadata(&self) -> Option<&PlayerMetadata<'static>> {
+        self.metadata.as_ref()
+    }
+
+    /// Gets the volume.
+    pub fn volume(&self) -> Option<f64> {
+        self.volume
+    }
+}
+
+/// Everything a status widget shows about a player, as returned by [`crate::MprisPlayer::state`].
+///
+/// Read the same way as a [`PlayerSnapshot`], with the loop status, shuffle, rate,
+/// position and capabilities on top.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerState {
+    playback_status: Option<PlaybackStatus>,
+    loop_status: Option<LoopStatus>,
+    shuffle: Option<bool>,
+    volume: Option<f64>,
+    rate: Option<f64>,
+    position: Option<Duration>,
+    metadata: Option<PlayerMetadata<'static>>,
+    capabilities: Capabilities,
+}
+
+impl PlayerState {
+    /// Picks the state out of the player properties.
+    pub(crate) fn new(mut properties: HashMap<String, OwnedValue>) -> Self {
+        let playback_status = properties
+            .get("PlaybackStatus")
+            .and_then(|value| <&str>::try_from(value).ok())
+            .and_then(|status| PlaybackStatus::from_str(status).ok());
+
+        let loop_status = properties
+            .get("LoopStatus")
+            .and_then(|value| <&str>::try_from(value).ok())
+            .and_then(|status| LoopStatus::from_str(status).ok());
+
+        let shuffle = properties
+            .get("Shuffle")
+            .and_then(|value| bool::try_from(value).ok());
+
+        let volume = properties
+            .get("Volume")
+            .and_then(|value| f64::try_from(value).ok());
+
+        let rate = properties
+            .get("Rate")
+            .and_then(|value| f64::try_from(value).ok());
+
+        // A negative position is as good as none.
+        let position = properties
+            .get("Position")
+            .and_then(|value| i64::try_from(value).ok())
+            .and_then(|position| u64::try_from(position).ok())
+            .map(Duration::from_micros);
+
+        let capabilities = Capabilities::new(&properties);
+
+        let metadata = properties
+            .remove("Metadata")
+            .and_then(|value| HashMap::<String, OwnedValue>::try_from(value).ok())
+            .map(owned_metadata);
+
+        Self {
+            playback_status,
+            loop_status,
+            shuffle,
+            volume,
+            rate,
+            position,
+            metadata,
+            capabilities,
+        }
+    }
+
+    /// Gets the playback status.
+    pub fn playback_status(&self) -> Option<&PlaybackStatus> {
+        self.playback_status.as_ref()
+    }
+
+    /// Gets the loop status, None for the players that don't loop.
+    pub fn loop_status(&self) -> Option<&LoopStatus> {
+        self.loop_status.as_ref()
+    }
+
+    /// Gets whether the tracks get shuffled, None for the players that don't shuffle.
+    pub fn shuffle(&self) -> Option<bool> {
+        self.shuffle
+    }
+
+    /// Gets the volume.
+    pub fn volume(&self) -> Option<f64> {
+        self.volume
+    }
+
+    /// Gets the playback rate.
+    pub fn rate(&self) -> Option<f64> {
+        self.rate
+    }
+
+    /// Gets the position within the current track, when the state got read.
+    pub fn position(&self) -> Option<Duration> {
+        self.position
+    }
+
+    /// Gets the metadata of the current track.
+    pub fn metadata(&self) -> Option<&PlayerMetadata<'static>> {
+        self.metadata.as_ref()
+    }
+
+    /// Gets the `Can*` capabilities, the ones the player left out being false.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+}
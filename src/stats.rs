@@ -0,0 +1,270 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime},
+};
+
+use tokio::sync::{broadcast, mpsc, watch};
+
+use crate::{
+    EventEnvelope, EventKind, MprisEvent, MprisOptions, MprisResult, PlayerIdentity,
+    filter::SharedEventFilter, hooks::SharedEventHooks, now_playing::NowPlayingTracker,
+    queue::EventQueue,
+};
+
+/// Runtime counters of a player, as returned by [`crate::Mpris::stats`].
+///
+/// Meant for finding out which player is flooding a long running watcher.
+#[derive(Debug, Clone, Default)]
+pub struct PlayerStats {
+    /// The emitted events, by kind.
+    events: HashMap<EventKind, u64>,
+
+    /// The D-Bus calls made to the player.
+    dbus_calls: u64,
+
+    /// The errors emitted for the player.
+    errors: u64,
+
+    /// When the player last sent a signal.
+    last_signal: Option<Instant>,
+}
+
+impl PlayerStats {
+    /// Gets how many events of this kind got emitted.
+    pub fn events(&self, kind: EventKind) -> u64 {
+        self.events.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// Gets how many events got emitted in total.
+    pub fn total_events(&self) -> u64 {
+        self.events.values().sum()
+    }
+
+    /// Gets how many D-Bus calls were made to the player.
+    pub fn dbus_calls(&self) -> u64 {
+        self.dbus_calls
+    }
+
+    /// Gets how many errors got emitted.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// Gets when the player last sent a signal.
+    pub fn last_signal(&self) -> Option<Instant> {
+        self.last_signal
+    }
+}
+
+/// The shared counters of every player, cheap to clone.
+///
+/// The players are kept after being detached, so the short lived ones still show up.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Stats(Arc<Mutex<HashMap<PlayerIdentity, PlayerStats>>>);
+
+impl Stats {
+    /// Updates the counters of a player.
+    fn update(&self, identity: &PlayerIdentity, update: impl FnOnce(&mut PlayerStats)) {
+        // A poisoned lock only means a panic mid-update, the counters are still usable.
+        let mut players = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        update(players.entry(identity.clone()).or_default());
+    }
+
+    pub(crate) fn record_event(&self, identity: &PlayerIdentity, kind: EventKind) {
+        self.update(identity, |stats| {
+            *stats.events.entry(kind).or_default() += 1
+        });
+    }
+
+    pub(crate) fn record_call(&self, identity: &PlayerIdentity) {
+        self.update(identity, |stats| stats.dbus_calls += 1);
+    }
+
+    pub(crate) fn record_error(&self, identity: &PlayerIdentity) {
+        self.update(identity, |stats| stats.errors += 1);
+    }
+
+    pub(crate) fn record_signal(&self, identity: &PlayerIdentity) {
+        self.update(identity, |stats| stats.last_signal = Some(Instant::now()));
+    }
+
+    /// Gets a copy of the counters of every player.
+    pub(crate) fn snapshot(&self) -> HashMap<PlayerIdentity, PlayerStats> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).clone()
+    }
+}
+
+/// Where an [`EventSender`] sends the events to.
+#[derive(Debug, Clone)]
+enum EventSink {
+    /// A plain channel, as handed to [`crate::MprisPlayer::watch`].
+    Channel(mpsc::UnboundedSender<MprisResult<MprisEvent>>),
+
+    /// The channel of a [`crate::Mpris`], which can be bounded.
+    Queue(Arc<EventQueue>),
+}
+
+/// The receiving half of an [`EventSender`] went away.
+#[derive(Debug)]
+pub(crate) struct ReceiverGone;
+
+/// An event sender counting what goes through it.
+#[derive(Debug, Clone)]
+pub(crate) struct EventSender {
+    sink: EventSink,
+    stats: Stats,
+    filter: SharedEventFilter,
+    hooks: SharedEventHooks,
+    now_playing: Option<NowPlayingTracker>,
+    subscribers: Option<broadcast::Sender<MprisResult<MprisEvent>>>,
+
+    /// The options to follow, and the fingerprint of the last event of each player.
+    options: Option<watch::Receiver<MprisOptions>>,
+    last: Arc<Mutex<HashMap<PlayerIdentity, u64>>>,
+}
+
+impl EventSender {
+    pub(crate) fn new(
+        sender: mpsc::UnboundedSender<MprisResult<MprisEvent>>,
+        stats: Stats,
+    ) -> Self {
+        Self::with_sink(EventSink::Channel(sender), stats)
+    }
+
+    /// Sends the events to the channel of a [`crate::Mpris`].
+    pub(crate) fn queue(queue: Arc<EventQueue>, stats: Stats) -> Self {
+        Self::with_sink(EventSink::Queue(queue), stats)
+    }
+
+    fn with_sink(sink: EventSink, stats: Stats) -> Self {
+        Self {
+            sink,
+            stats,
+            filter: SharedEventFilter::default(),
+            hooks: SharedEventHooks::default(),
+            now_playing: None,
+            subscribers: None,
+            options: None,
+            last: Arc::default(),
+        }
+    }
+
+    /// Follows the options, for dropping the duplicate events.
+    pub(crate) fn with_options(mut self, options: watch::Receiver<MprisOptions>) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    /// Also hands a copy of everything sent out to the subscribers.
+    pub(crate) fn with_subscribers(
+        mut self,
+        subscribers: broadcast::Sender<MprisResult<MprisEvent>>,
+    ) -> Self {
+        self.subscribers = Some(subscribers);
+        self
+    }
+
+    /// Drops the events the closure doesn't let through, before they're counted or sent.
+    pub(crate) fn with_filter(mut self, filter: SharedEventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Follows the active player with the events let through.
+    pub(crate) fn with_now_playing(mut self, now_playing: NowPlayingTracker) -> Self {
+        self.now_playing = Some(now_playing);
+        self
+    }
+
+    /// Runs the hooks on the events let through, right before they're sent.
+    pub(crate) fn with_hooks(mut self, hooks: SharedEventHooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Sends out an event or an error, counting it for the player it belongs to.
+    ///
+    /// The events dropped by the filter closure count as sent. Waits for room in
+    /// a full bounded channel, if its overflow policy says so.
+    ///
+    /// Errors once the receiver went away, which is when the watch tasks stop.
+    pub(crate) async fn send(&self, result: MprisResult<MprisEvent>) -> Result<(), ReceiverGone> {
+        self.send_at(result, SystemTime::now()).await
+    }
+
+    /// Sends out an event or an error, for a change noticed at an earlier time.
+    pub(crate) async fn send_at(
+        &self,
+        result: MprisResult<MprisEvent>,
+        received_at: SystemTime,
+    ) -> Result<(), ReceiverGone> {
+        match &result {
+            Ok(event) if !self.filter.matches(event) || self.duplicate(event) => return Ok(()),
+            Ok(event) => {
+                self.stats.record_event(event.identity(), event.kind());
+                self.hooks.run(event);
+
+                if let Some(now_playing) = &self.now_playing {
+                    now_playing.track(event);
+                }
+            }
+            Err(err) => {
+                if let Some(identity) = err
+                    .bus()
+                    .and_then(|bus| PlayerIdentity::new(bus.into()).ok())
+                {
+                    self.stats.record_error(&identity);
+                }
+            }
+        }
+
+        // Only copied for the subscribers there are.
+        if let Some(subscribers) = &self.subscribers
+            && subscribers.receiver_count() > 0
+        {
+            let _ = subscribers.send(result.clone());
+        }
+
+        match &self.sink {
+            EventSink::Channel(sender) => sender.send(result).map_err(|_| ReceiverGone),
+            EventSink::Queue(queue) => {
+                let result = result.map(|event| EventEnvelope::new(event, received_at));
+                queue.send(result).await
+            }
+        }
+    }
+
+    /// Returns `true` if the event is the same as the last one of its player, when dropping those.
+    fn duplicate(&self, event: &MprisEvent) -> bool {
+        let coalesce = self
+            .options
+            .as_ref()
+            .is_some_and(|options| options.borrow().coalesce_duplicates());
+
+        if !coalesce {
+            return false;
+        }
+
+        let fingerprint = event.fingerprint();
+        let mut last = self.last.lock().unwrap_or_else(|err| err.into_inner());
+
+        match fingerprint {
+            Some(fingerprint) => {
+                last.insert(event.identity().clone(), fingerprint) == Some(fingerprint)
+            }
+            None => {
+                last.remove(event.identity());
+                false
+            }
+        }
+    }
+
+    /// Waits for the receiving half to be dropped.
+    pub(crate) async fn closed(&self) {
+        match &self.sink {
+            EventSink::Channel(sender) => sender.closed().await,
+            EventSink::Queue(queue) => queue.closed().await,
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub enum PlaybackStatus {
 }
 
 /// Loop status of a player.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum LoopStatus {
     None,
     Track,
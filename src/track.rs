@@ -0,0 +1,93 @@
+use crate::PlayerMetadata;
+
+/// The `mpris:trackid` players use when there's no track.
+pub(crate) const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+/// What tells the tracks of a player apart, for detecting track changes.
+///
+/// Some players reuse the same `mpris:trackid` for every track, which makes it useless
+/// for telling when the track changed. Set with [`crate::MprisOptions::with_track_identity`].
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use mprizzle::{PlayerMetadata, TrackIdentity};
+/// use zvariant::Value;
+///
+/// let first = PlayerMetadata::new(HashMap::from([
+///     ("mpris:trackid".to_string(), Value::from("/same")),
+///     ("xesam:title".to_string(), Value::from("First")),
+/// ]));
+///
+/// let second = PlayerMetadata::new(HashMap::from([
+///     ("mpris:trackid".to_string(), Value::from("/same")),
+///     ("xesam:title".to_string(), Value::from("Second")),
+/// ]));
+///
+/// assert_eq!(TrackIdentity::TrackId.key(&first), TrackIdentity::TrackId.key(&second));
+/// assert_ne!(TrackIdentity::TitleArtistLength.key(&first), TrackIdentity::TitleArtistLength.key(&second));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TrackIdentity {
+    /// The `mpris:trackid`, falling back to [`TrackIdentity::TitleArtistLength`]
+    /// when it's missing or is the "no track" one.
+    #[default]
+    TrackId,
+
+    /// The `xesam:title`, `xesam:artist` and `mpris:length`.
+    TitleArtistLength,
+
+    /// The `xesam:url`.
+    Url,
+
+    /// Any combination of metadata fields (e.g., `xesam:album` and `xesam:trackNumber`).
+    Fields(Vec<String>),
+}
+
+/// A key telling a track apart from the others, see [`TrackIdentity::key`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TrackKey(Vec<Option<String>>);
+
+impl TrackIdentity {
+    /// Gets the key of the track the metadata is about.
+    ///
+    /// Returns None when none of the fields are present, like when nothing is playing.
+    pub fn key(&self, metadata: &PlayerMetadata) -> Option<TrackKey> {
+        match self {
+            TrackIdentity::TrackId => {
+                let track_id = metadata
+                    .get("mpris:trackid")
+                    .map(|track_id| track_id.to_string())
+                    .filter(|track_id| !track_id.contains(NO_TRACK));
+
+                match track_id {
+                    Some(track_id) => Some(TrackKey(vec![Some(track_id)])),
+                    None => TrackIdentity::TitleArtistLength.key(metadata),
+                }
+            }
+            TrackIdentity::TitleArtistLength => {
+                key_of(metadata, ["xesam:title", "xesam:artist", "mpris:length"])
+            }
+            TrackIdentity::Url => key_of(metadata, ["xesam:url"]),
+            TrackIdentity::Fields(fields) => key_of(metadata, fields.iter().map(String::as_str)),
+        }
+    }
+}
+
+/// Gets the key made out of these metadata fields.
+fn key_of<'f>(
+    metadata: &PlayerMetadata,
+    fields: impl IntoIterator<Item = &'f str>,
+) -> Option<TrackKey> {
+    let values: Vec<Option<String>> = fields
+        .into_iter()
+        .map(|field| metadata.get(field).map(|value| value.to_string()))
+        .collect();
+
+    values
+        .iter()
+        .any(Option::is_some)
+        .then_some(TrackKey(values))
+}